@@ -0,0 +1,103 @@
+// benchmarks for the template search used by the image steps
+//
+// every folder in benches/screenshots with a `screen.png` and a `template.png` is benchmarked as is,
+// on top of that there are generated desktops so there is always something to compare against
+
+use std::fs;
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::{RgbaImage, Rgba, GenericImage, GenericImageView};
+use image::io::Reader;
+
+// only part of it is used here, its tests are compiled along (though never run) by `cargo test` and `--all-targets`,
+// so they can't use anything from the rest of macro_logic
+#[path = "../src/macro_logic/image_search.rs"]
+#[allow(dead_code, unused_imports)]
+mod image_search;
+
+use image_search::{find_image, MatchMode, Template};
+
+/// flat colored "windows" with some "text" in them, close enough to what a real desktop compresses to
+fn generated_desktop(width: u32, height: u32) -> RgbaImage {
+    let mut seed: u32 = 7;
+    let mut next = move || {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        seed >> 16
+    };
+
+    let mut screen = RgbaImage::from_pixel(width, height, Rgba([32, 96, 160, 255]));
+
+    for _ in 0..40 {
+        let (x, y) = (next() % width, next() % height);
+        let (w, h) = ((next() % 800 + 100).min(width - x), (next() % 600 + 80).min(height - y));
+        let shade = (next() % 60 + 190) as u8;
+
+        for py in y..y + h {
+            for px in x..x + w {
+                // every few pixels a dark "glyph" dot, like lines of text
+                let is_text = (py - y) % 18 < 10 && (px - x) % 7 < 3 && next() % 3 == 0;
+                let value = if is_text { 30 } else { shade };
+
+                screen.put_pixel(px, py, Rgba([value, value, value, 255]));
+            }
+        }
+    }
+
+    screen
+}
+
+fn cases() -> Vec<(String, RgbaImage, RgbaImage)> {
+    let mut cases = Vec::new();
+
+    for (name, width, height) in [("1080p", 1920, 1080), ("4k", 3840, 2160)] {
+        let screen = generated_desktop(width, height);
+
+        let mut present = RgbaImage::new(90, 30);
+        present.copy_from(&screen.view(width / 2, height / 2, 90, 30), 0, 0);
+
+        // a button that isn't anywhere on the screen, which is what a waiting step sees most of the time
+        let absent = RgbaImage::from_fn(90, 30, |x, y| {
+            if x == 0 || y == 0 || x == 89 || y == 29 { Rgba([0, 120, 215, 255]) } else { Rgba([225, 225, 225, 255]) }
+        });
+
+        cases.push((format!("{}-present", name), screen.clone(), present));
+        cases.push((format!("{}-absent", name), screen, absent));
+    }
+
+    let stored = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches").join("screenshots");
+
+    if let Ok(entries) = fs::read_dir(stored) {
+        for entry in entries.flatten() {
+            let (screen_path, template_path) = (entry.path().join("screen.png"), entry.path().join("template.png"));
+
+            if let (Ok(screen), Ok(template)) = (Reader::open(&screen_path), Reader::open(&template_path)) {
+                if let (Ok(screen), Ok(template)) = (screen.decode(), template.decode()) {
+                    cases.push((entry.file_name().to_string_lossy().into_owned(), screen.into_rgba(), template.into_rgba()));
+                }
+            }
+        }
+    }
+
+    cases
+}
+
+fn search_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_image");
+    group.sample_size(10);
+
+    for (name, screen, template) in cases() {
+        for mode in [MatchMode::Exact, MatchMode::Grayscale, MatchMode::ColorInsensitive, MatchMode::Edges] {
+            let prepared = Template::new(&template, mode);
+
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", mode), &name), &screen, |b, screen| {
+                b.iter(|| find_image(screen, &prepared, 0.05))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, search_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+use anyhow::{Result, anyhow};
+use image::{RgbaImage, GenericImageView};
+
+use super::image_search::{self, MatchMode, Template};
+
+/// an image that is found before the image of the step, the step's image is then only searched for
+/// in a region around it, e.g. the "Delete" button in the row of one specific label
+#[derive(Clone, Debug)]
+pub struct Anchor {
+    pub image: Option<RgbaImage>,
+    pub allowed_diff: f32,
+    pub mode: MatchMode,
+    pub x: i32,             // top left corner of the region in pixels from the top left corner of the found anchor
+    pub y: i32,
+    pub width: u32,
+    pub height: u32
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        // a band across the row of the anchor, the most common case is a button next to a label
+        Self {
+            image: None,
+            allowed_diff: 0.0,
+            mode: Default::default(),
+            x: 0,
+            y: -50,
+            width: 600,
+            height: 100
+        }
+    }
+}
+
+impl Anchor {
+    pub fn prepare(&self) -> Result<Template> {
+        let template = Template::new(self.image.as_ref().ok_or(anyhow!("Missing anchor image data"))?, self.mode);
+
+        if template.is_empty() {
+            return Err(anyhow!("Anchor image has no pixels to search for, it's either fully transparent or too small for this match mode"));
+        }
+
+        Ok(template)
+    }
+
+    /// the top left corner of the anchor on the screen
+    pub fn find(&self, screen: &RgbaImage, template: &Template) -> Option<(u32, u32)> {
+        image_search::find_image(screen, template, self.allowed_diff)
+    }
+
+    /// the region around an anchor found at `anchor_position` as x, y, width, height, clipped to the screen
+    pub fn region(&self, anchor_position: (u32, u32), screen_width: u32, screen_height: u32) -> Option<(u32, u32, u32, u32)> {
+        let left = (anchor_position.0 as i64 + self.x as i64).max(0);
+        let top = (anchor_position.1 as i64 + self.y as i64).max(0);
+        let right = (anchor_position.0 as i64 + self.x as i64 + self.width as i64).min(screen_width as i64);
+        let bottom = (anchor_position.1 as i64 + self.y as i64 + self.height as i64).min(screen_height as i64);
+
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some((left as u32, top as u32, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    /// searches for the template only inside the region around the anchor, the position is on the whole screen
+    pub fn find_in_region(&self, screen: &RgbaImage, anchor_position: (u32, u32), template: &Template, allowed_diff: f32) -> Option<(u32, u32)> {
+        let (left, top, width, height) = self.region(anchor_position, screen.width(), screen.height())?;
+        let region = screen.view(left, top, width, height).to_image();
+
+        image_search::find_image(&region, template, allowed_diff).map(|(x, y)| (left + x, top + y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::Anchor;
+    use crate::macro_logic::image_search::{MatchMode, Template};
+
+    fn anchor(x: i32, y: i32, width: u32, height: u32) -> Anchor {
+        Anchor { x, y, width, height, ..Default::default() }
+    }
+
+    #[test]
+    fn region_is_relative_and_clipped() {
+        assert_eq!(anchor(10, -5, 20, 10).region((50, 50), 200, 200), Some((60, 45, 20, 10)));
+        assert_eq!(anchor(-100, -100, 120, 120).region((50, 50), 200, 200), Some((0, 0, 70, 70)));
+        assert_eq!(anchor(100, 0, 20, 20).region((150, 0), 200, 200), None);
+    }
+
+    #[test]
+    fn only_the_region_is_searched() {
+        // the same red dot twice, the anchor region only covers the second one
+        let mut screen = RgbaImage::from_pixel(40, 10, Rgba([0, 0, 0, 255]));
+        screen.put_pixel(5, 5, Rgba([255, 0, 0, 255]));
+        screen.put_pixel(30, 5, Rgba([255, 0, 0, 255]));
+
+        let dot = Template::new(&RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255])), MatchMode::Exact);
+
+        assert_eq!(anchor(5, -5, 10, 10).find_in_region(&screen, (20, 5), &dot, 0.0), Some((30, 5)));
+        assert_eq!(anchor(-5, -5, 10, 10).find_in_region(&screen, (20, 5), &dot, 0.0), None);
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{Result, anyhow};
+use image::RgbaImage;
+
+use super::image_search::Candidate;
+use super::image_target::ImageTarget;
+
+/// how much above the lowest working allowed difference the suggestion is when there's nothing to stay below,
+/// so a slightly different render of the same button still matches
+const SUGGESTION_MARGIN_PERCENT: u32 = 2;
+
+/// what a step's images come closest to on sample screenshots where they should and shouldn't be found
+pub struct Calibration {
+    positives: Vec<Candidate>,      // the best place of every screenshot the images should be found on
+    negatives: Vec<Candidate>       // and of every screenshot they must not be found on
+}
+
+impl Calibration {
+    /// a step finds any of its images, so every screenshot is represented by the image that came closest on it,
+    /// with an anchor only its region is searched like the step does, the anchor keeps its own accuracy
+    pub fn new(target: &ImageTarget, positives: &[RgbaImage], negatives: &[RgbaImage]) -> Result<Self> {
+        if positives.is_empty() {
+            return Err(anyhow!("At least one screenshot the image should be found on is needed"));
+        }
+
+        let templates = target.prepare()?;
+
+        // Err when the anchor isn't on the screenshot, None when there's nothing to compare
+        let closest = |screenshot: &RgbaImage| -> Result<Option<Candidate>> {
+            let region = target.search_region(screenshot, &templates).ok_or(anyhow!("The anchor isn't found on one of the screenshots"))?;
+
+            Ok(target.best_candidates(screenshot, &templates, region, 1).into_iter()
+                .filter_map(|candidates| candidates.into_iter().next())
+                .min_by(|a, b| a.difference.total_cmp(&b.difference)))
+        };
+
+        let positives = positives.iter()
+            .map(|screenshot| closest(screenshot)?.ok_or(anyhow!("The image is bigger than one of the screenshots or the region of its anchor")))
+            .collect::<Result<Vec<Candidate>>>()?;
+
+        // without the anchor or anything to compare the image is never found, so it's fine for the negatives
+        let negatives = negatives.iter().filter_map(|screenshot| closest(screenshot).ok().flatten()).collect();
+
+        Ok(Calibration { positives, negatives })
+    }
+
+    /// whole percents of allowed difference that find the images on every positive and on none of the negative screenshots,
+    /// as the lowest and highest working one
+    pub fn working_range(&self) -> Option<(u32, u32)> {
+        let works = |percent: u32| {
+            let allowed_diff = percent as f32 / 100.0;
+
+            self.positives.iter().all(|candidate| candidate.matches(allowed_diff)) &&
+            !self.negatives.iter().any(|candidate| candidate.matches(allowed_diff))
+        };
+
+        // a higher allowed difference only ever matches more, so the working percents are next to each other
+        let lowest = (0..=100).find(|percent| works(*percent))?;
+        let highest = (lowest..=100).take_while(|percent| works(*percent)).last()?;
+
+        Some((lowest, highest))
+    }
+
+    /// the allowed difference to use, in the middle of the working range so both sides have some room,
+    /// or a bit above the lowest one when there are no negatives to stay away from
+    pub fn suggestion(&self) -> Option<f32> {
+        let (lowest, highest) = self.working_range()?;
+
+        let percent = match self.negatives.is_empty() {
+            true => (lowest + SUGGESTION_MARGIN_PERCENT).min(highest),
+            false => (lowest + highest) / 2,
+        };
+
+        Some(percent as f32 / 100.0)
+    }
+
+    /// the highest allowed difference any positive needs, the lowest one that finds all of them
+    pub fn needed_by_positives(&self) -> f32 {
+        self.positives.iter().map(|candidate| candidate.difference).fold(0.0, f32::max)
+    }
+
+    /// the smallest difference of a negative, anything at or above it finds the images where they must not be found
+    pub fn closest_negative(&self) -> Option<f32> {
+        self.negatives.iter().map(|candidate| candidate.difference).reduce(f32::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::Calibration;
+    use crate::macro_logic::anchor::Anchor;
+    use crate::macro_logic::image_search::MatchMode;
+    use crate::macro_logic::image_target::ImageTarget;
+    use crate::macro_logic::image_search::tests::screen_with_square;
+
+    fn red_square() -> ImageTarget {
+        ImageTarget { images: vec![RgbaImage::from_pixel(3, 3, Rgba([200, 0, 0, 255]))], mode: MatchMode::Exact, ..Default::default() }
+    }
+
+    #[test]
+    fn range_separates_positives_from_negatives() {
+        // the button a bit darker should still be found, the button in a different color shouldn't
+        let positives = [screen_with_square([200, 0, 0]), screen_with_square([180, 0, 0])];
+        let negatives = [screen_with_square([100, 0, 0])];
+
+        let calibration = Calibration::new(&red_square(), &positives, &negatives).unwrap();
+        let (lowest, highest) = calibration.working_range().unwrap();
+
+        // only one of the three channels differs, so 20 levels of red need 20 / (255 * sqrt(3)) = 4.5%,
+        // and 100 levels are found from 22.6% on
+        assert_eq!((lowest, highest), (5, 22));
+        assert_eq!(calibration.suggestion(), Some(0.13));
+        assert!(calibration.closest_negative().unwrap() > calibration.needed_by_positives());
+    }
+
+    #[test]
+    fn no_negatives_suggests_a_margin() {
+        let calibration = Calibration::new(&red_square(), &[screen_with_square([200, 0, 0])], &[]).unwrap();
+
+        assert_eq!(calibration.working_range(), Some((0, 100)));
+        assert_eq!(calibration.suggestion(), Some(0.02));
+    }
+
+    #[test]
+    fn overlapping_samples_have_no_range() {
+        let calibration = Calibration::new(&red_square(), &[screen_with_square([180, 0, 0])], &[screen_with_square([200, 0, 0])]).unwrap();
+
+        assert_eq!(calibration.working_range(), None);
+        assert_eq!(calibration.suggestion(), None);
+    }
+
+    #[test]
+    fn only_the_anchor_region_counts() {
+        // a perfect square left of the green anchor, the darker one right of it is what the step finds
+        let mut positive = screen_with_square([180, 0, 0]);
+
+        for y in 4..7 {
+            for x in 1..4 {
+                positive.put_pixel(x, y, Rgba([200, 0, 0, 255]));
+            }
+        }
+
+        positive.put_pixel(10, 5, Rgba([0, 255, 0, 255]));
+
+        let anchor = |color: [u8; 3]| Some(Anchor {
+            image: Some(RgbaImage::from_pixel(1, 1, Rgba([color[0], color[1], color[2], 255]))),
+            mode: MatchMode::Exact,
+            x: 0,
+            y: -5,
+            width: 10,
+            height: 10,
+            ..Default::default()
+        });
+
+        let without_anchor = Calibration::new(&red_square(), &[positive.clone()], &[]).unwrap();
+        let with_anchor = Calibration::new(&ImageTarget { anchor: anchor([0, 255, 0]), ..red_square() }, &[positive.clone()], &[]).unwrap();
+
+        assert_eq!(without_anchor.needed_by_positives(), 0.0);
+        assert!(with_anchor.needed_by_positives() > 0.04);
+
+        // the anchor has to be on the screenshots the image should be found on
+        assert!(Calibration::new(&ImageTarget { anchor: anchor([0, 0, 255]), ..red_square() }, &[positive], &[]).is_err());
+    }
+}
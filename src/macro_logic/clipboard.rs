@@ -0,0 +1,67 @@
+use anyhow::{Result, anyhow};
+use clippers::{Clipboard, ClipperData};
+use image::RgbaImage;
+
+/// what the clipboard holds, as far as the steps can use it
+#[derive(Clone, Debug)]
+pub enum ClipboardContent {
+    Text(String),
+    Image(RgbaImage)
+}
+
+// images of the image crate can't be compared, so the size and the pixels are
+impl PartialEq for ClipboardContent {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ClipboardContent::Text(text), ClipboardContent::Text(other_text)) => text == other_text,
+            (ClipboardContent::Image(image), ClipboardContent::Image(other_image)) => {
+                image.dimensions() == other_image.dimensions() && **image == **other_image
+            },
+            _ => false,
+        }
+    }
+}
+
+impl ClipboardContent {
+    /// None when the clipboard is empty or holds something else, e.g. files
+    pub fn read() -> Option<Self> {
+        match Clipboard::get().read()? {
+            ClipperData::Text(text) => Some(ClipboardContent::Text(text.to_string())),
+            ClipperData::Image(image) => RgbaImage::from_raw(
+                image.width(),
+                image.height(),
+                image.pixels().flat_map(|pix| [pix[0], pix[1], pix[2], pix[3]]).collect()
+            ).map(ClipboardContent::Image),
+            _ => None,
+        }
+    }
+
+    pub fn write(&self) -> Result<()> {
+        let mut clipboard = Clipboard::get();
+
+        let res = match self {
+            ClipboardContent::Text(text) => clipboard.write_text(text),
+            ClipboardContent::Image(image) => clipboard.write_image(image.width(), image.height(), image),
+        };
+
+        res.map_err(|err| anyhow!("Failed to write to the clipboard: {:?}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::ClipboardContent;
+
+    #[test]
+    fn images_compare_by_size_and_pixels() {
+        let image = ClipboardContent::Image(RgbaImage::from_pixel(2, 3, Rgba([255, 0, 0, 255])));
+
+        assert!(image == ClipboardContent::Image(RgbaImage::from_pixel(2, 3, Rgba([255, 0, 0, 255]))));
+        assert!(image != ClipboardContent::Image(RgbaImage::from_pixel(2, 3, Rgba([0, 0, 255, 255]))));
+        // same pixels in another shape
+        assert!(image != ClipboardContent::Image(RgbaImage::from_pixel(3, 2, Rgba([255, 0, 0, 255]))));
+        assert!(image != ClipboardContent::Text("".into()));
+    }
+}
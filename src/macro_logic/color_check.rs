@@ -0,0 +1,184 @@
+use anyhow::{Result, anyhow};
+use image::RgbaImage;
+
+use super::image_search::{self, MatchMode, Template};
+use super::Settings;
+use super::screen;
+
+/// where on the screen a color is read and what it's compared to
+#[derive(Clone, Debug)]
+pub struct ColorCheck {
+    pub relative_to_image: bool,
+    pub image: Option<RgbaImage>,       // when relative, x and y are pixels from the top left corner of where this image is found
+    pub image_allowed_diff: f32,
+    pub image_mode: MatchMode,
+    pub x: i32,                         // desktop coordinates when not relative, any monitor - from the target window of the macro if it has one
+    pub y: i32,
+    pub size: u32,                      // side of the square around the point that is averaged, 1 is a single pixel
+    pub color: [u8; 3],
+    pub allowed_diff: f32
+}
+
+impl Default for ColorCheck {
+    fn default() -> Self {
+        Self {
+            relative_to_image: false,
+            image: None,
+            image_allowed_diff: 0.0,
+            image_mode: Default::default(),
+            x: 0,
+            y: 0,
+            size: 1,
+            color: [0, 0, 0],
+            allowed_diff: 0.0
+        }
+    }
+}
+
+impl ColorCheck {
+    /// the template of the image the position is relative to, None for absolute positions
+    pub fn prepare_anchor(&self) -> Result<Option<Template>> {
+        if !self.relative_to_image {
+            return Ok(None);
+        }
+
+        let template = Template::new(self.image.as_ref().ok_or(anyhow!("Missing image data"))?, self.image_mode);
+
+        if template.is_empty() {
+            return Err(anyhow!("Image has no pixels to search for, it's either fully transparent or too small for this match mode"));
+        }
+
+        Ok(Some(template))
+    }
+
+    /// takes a screenshot and checks the color, a relative check fails if its image isn't where the macro searches
+    pub fn matches_screen(&self, anchor: Option<&Template>, settings: &Settings) -> Result<bool> {
+        let (screen, (x, y)) = match anchor {
+            Some(template) => {
+                let screen = screen::capture_for(settings)?;
+
+                match image_search::find_image(&screen.image, template, self.image_allowed_diff) {
+                    Some((found_x, found_y)) => (screen, (found_x as i64 + self.x as i64, found_y as i64 + self.y as i64)),
+                    None => return Ok(false),
+                }
+            },
+            // an absolute point is on exactly one monitor, no matter which ones the image steps search
+            None => {
+                let (origin_x, origin_y) = screen::origin(settings)?;
+                let (desktop_x, desktop_y) = (origin_x + self.x as f64, origin_y + self.y as f64);
+
+                let screen = screen::capture_at(desktop_x, desktop_y)?;
+                let pixel = screen.to_pixel(desktop_x, desktop_y);
+
+                (screen, pixel)
+            }
+        };
+
+        let color = average_color(&screen.image, x, y, self.size).ok_or(anyhow!("The point to check is outside of the screen"))?;
+
+        Ok(colors_match(color, self.color, self.allowed_diff))
+    }
+
+    /// reads the position and color under the mouse cursor into the check, the image of a relative check
+    /// has to be on the same monitor as the cursor
+    pub fn pick_from_cursor(&mut self, settings: &Settings) -> Result<()> {
+        let (cursor_x, cursor_y) = screen::cursor_position();
+        let screen = screen::capture_at(cursor_x, cursor_y)?;
+        let (pixel_x, pixel_y) = screen.to_pixel(cursor_x, cursor_y);
+
+        let (x, y) = match self.prepare_anchor()? {
+            Some(template) => {
+                let (found_x, found_y) = image_search::find_image(&screen.image, &template, self.image_allowed_diff)
+                    .ok_or(anyhow!("The image the position is relative to isn't on the monitor under the cursor"))?;
+
+                (pixel_x - found_x as i64, pixel_y - found_y as i64)
+            },
+            None => {
+                let (x, y) = screen::relative_cursor_position(settings)?;
+
+                (x.round() as i64, y.round() as i64)
+            }
+        };
+
+        self.color = average_color(&screen.image, pixel_x, pixel_y, self.size).ok_or(anyhow!("The mouse cursor is outside of the screen"))?;
+        self.x = x as i32;
+        self.y = y as i32;
+
+        Ok(())
+    }
+}
+
+/// average color of the `size` x `size` square centered on (x, y), the parts outside of the image are left out
+pub fn average_color(image: &RgbaImage, x: i64, y: i64, size: u32) -> Option<[u8; 3]> {
+    let size = size.max(1) as i64;
+    let (left, top) = (x - (size - 1) / 2, y - (size - 1) / 2);
+
+    let mut total = [0u64; 3];
+    let mut count = 0;
+
+    for py in top.max(0)..(top + size).min(image.height() as i64) {
+        for px in left.max(0)..(left + size).min(image.width() as i64) {
+            let pixel = image.get_pixel(px as u32, py as u32);
+
+            for (total, value) in total.iter_mut().zip(pixel.0.iter()) {
+                *total += *value as u64;
+            }
+
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return None;
+    }
+
+    Some([
+        ((total[0] + count / 2) / count) as u8,
+        ((total[1] + count / 2) / count) as u8,
+        ((total[2] + count / 2) / count) as u8
+    ])
+}
+
+/// same scale as the image search - 0.0 only matches the exact color, 1.0 matches anything
+pub fn colors_match(a: [u8; 3], b: [u8; 3], allowed_diff: f32) -> bool {
+    let distance_sq: f32 = a.iter().zip(b.iter()).map(|(a, b)| {
+        let diff = *a as f32 - *b as f32;
+        diff * diff
+    }).sum();
+
+    let max_diff = allowed_diff.clamp(0.0, 1.0) * 255.0;
+
+    distance_sq <= max_diff * max_diff * 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::{average_color, colors_match};
+
+    #[test]
+    fn single_pixel_is_read_as_is() {
+        let mut image = RgbaImage::from_pixel(10, 10, Rgba([0, 0, 0, 255]));
+        image.put_pixel(4, 6, Rgba([10, 20, 30, 255]));
+
+        assert_eq!(average_color(&image, 4, 6, 1), Some([10, 20, 30]));
+    }
+
+    #[test]
+    fn region_is_averaged_and_clipped() {
+        // left half black, right half white
+        let image = RgbaImage::from_fn(10, 10, |x, _| if x < 5 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) });
+
+        assert_eq!(average_color(&image, 4, 5, 2), Some([128, 128, 128]));
+        assert_eq!(average_color(&image, 0, 0, 3), Some([0, 0, 0]));
+        assert_eq!(average_color(&image, 20, 20, 3), None);
+    }
+
+    #[test]
+    fn allowed_difference_is_respected() {
+        assert!(colors_match([100, 100, 100], [100, 100, 100], 0.0));
+        assert!(!colors_match([100, 100, 100], [105, 100, 100], 0.0));
+        assert!(colors_match([100, 100, 100], [105, 100, 100], 0.05));
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use autopilot::mouse;
+use image::RgbaImage;
+
+use super::image_search::{self, MatchMode, Template};
+use super::macro_base::ClickPoint;
+use super::Settings;
+use super::screen;
+
+/// presses a mouse button on one place and releases it on another, e.g. to reorder items or move files
+#[derive(Clone, Debug)]
+pub struct Drag {
+    pub source: DragEnd,
+    pub destination: DragEnd,
+    pub button: mouse::Button,
+    pub press_hold_ms: u64,     // held at the source before moving, some applications only start a drag after a moment
+    pub move_ms: u64,           // how long the way from the source to the destination takes
+    pub release_hold_ms: u64    // held at the destination before releasing, drop targets often react to hovering first
+}
+
+/// where a drag starts or ends, either where an image is found or a fixed point
+#[derive(Clone, Debug)]
+pub struct DragEnd {
+    pub on_image: bool,
+    pub image: Option<RgbaImage>,       // kept when switching to a fixed point, so switching back doesn't lose it
+    pub point: ClickPoint,              // where on the found image the mouse goes
+    pub allowed_diff: f32,
+    pub mode: MatchMode,
+    pub x: i32,                         // desktop coordinates when not on an image, from the target window of the macro if it has one
+    pub y: i32
+}
+
+impl Default for Drag {
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+            destination: Default::default(),
+            button: mouse::Button::Left,
+            press_hold_ms: 200,
+            move_ms: 500,
+            release_hold_ms: 200
+        }
+    }
+}
+
+impl Default for DragEnd {
+    fn default() -> Self {
+        Self {
+            on_image: false,
+            image: None,
+            point: Default::default(),
+            allowed_diff: 0.0,
+            mode: Default::default(),
+            x: 0,
+            y: 0
+        }
+    }
+}
+
+impl Drag {
+    /// time between two moves of the mouse on the way to the destination
+    pub const STEP: Duration = Duration::from_millis(10);
+
+    /// the points the mouse goes through after leaving `from`, one for every `STEP`, the last one is `to`
+    pub fn path(from: (f64, f64), to: (f64, f64), move_ms: u64) -> Vec<(f64, f64)> {
+        let steps = (move_ms / Drag::STEP.as_millis() as u64).max(1);
+
+        (1..=steps).map(|step| {
+            let progress = step as f64 / steps as f64;
+
+            (from.0 + (to.0 - from.0) * progress, from.1 + (to.1 - from.1) * progress)
+        }).collect()
+    }
+}
+
+impl DragEnd {
+    /// the template of the image, None for fixed points
+    pub fn prepare(&self) -> Result<Option<Template>> {
+        if !self.on_image {
+            return Ok(None);
+        }
+
+        let template = Template::new(self.image.as_ref().ok_or(anyhow!("Missing image data"))?, self.mode);
+
+        if template.is_empty() {
+            return Err(anyhow!("Image has no pixels to search for, it's either fully transparent or too small for this match mode"));
+        }
+
+        Ok(Some(template))
+    }
+
+    /// where the mouse goes in desktop coordinates, None while the image isn't on the screen
+    pub fn locate(&self, template: Option<&Template>, settings: &Settings) -> Result<Option<(f64, f64)>> {
+        let (template, image) = match (template, &self.image) {
+            (Some(template), Some(image)) => (template, image),
+            _ => {
+                let (origin_x, origin_y) = screen::origin(settings)?;
+
+                return Ok(Some((origin_x + self.x as f64, origin_y + self.y as f64)));
+            }
+        };
+
+        let screen = screen::capture_for(settings)?;
+
+        Ok(image_search::find_image(&screen.image, template, self.allowed_diff).map(|(found_x, found_y)| {
+            let (offset_x, offset_y) = self.point.to_offset(image.width(), image.height());
+
+            screen.to_desktop(found_x as f64 + offset_x, found_y as f64 + offset_y)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Drag;
+
+    #[test]
+    fn path_ends_at_the_destination() {
+        let path = Drag::path((0.0, 100.0), (100.0, 0.0), 40);
+
+        assert_eq!(path, vec![(25.0, 75.0), (50.0, 50.0), (75.0, 25.0), (100.0, 0.0)]);
+    }
+
+    #[test]
+    fn instant_move_still_reaches_the_destination() {
+        assert_eq!(Drag::path((10.0, 10.0), (-20.0, 30.0), 0), vec![(-20.0, 30.0)]);
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+
+/// how often the waiting checks whether it's still wanted
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// blocks until F8 is pressed anywhere on the desktop, also while another window has the focus,
+/// false when `waiting` was set to false before that
+///
+/// F8 is taken from the other applications while waiting, so it has to run on a thread of its own
+pub fn wait_for_f8(waiting: Arc<Mutex<bool>>) -> Result<bool> {
+    system_wait_for_f8(&waiting)
+}
+
+#[cfg(target_os = "windows")]
+fn system_wait_for_f8(waiting: &Mutex<bool>) -> Result<bool> {
+    use std::{mem, ptr, thread};
+    use winapi::um::winuser::{self, MSG};
+
+    // the id only has to be unique for the thread, which is this waiting's own
+    const HOTKEY_ID: i32 = 1;
+
+    unsafe {
+        if winuser::RegisterHotKey(ptr::null_mut(), HOTKEY_ID, winuser::MOD_NOREPEAT as u32, winuser::VK_F8 as u32) == 0 {
+            return Err(anyhow!("F8 is already a shortcut of another application"));
+        }
+
+        let mut pressed = false;
+
+        while *waiting.lock().unwrap() {
+            let mut msg: MSG = mem::zeroed();
+
+            if winuser::PeekMessageW(&mut msg, ptr::null_mut(), winuser::WM_HOTKEY, winuser::WM_HOTKEY, winuser::PM_REMOVE) != 0 {
+                pressed = true;
+                break;
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        winuser::UnregisterHotKey(ptr::null_mut(), HOTKEY_ID);
+
+        Ok(pressed)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn system_wait_for_f8(waiting: &Mutex<bool>) -> Result<bool> {
+    use std::os::raw::c_int;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::{mem, ptr, thread};
+    use x11::{keysym, xlib};
+
+    // the default handler of Xlib ends the whole process, a key another client already grabbed is only an error here
+    static GRAB_FAILED: AtomicBool = AtomicBool::new(false);
+
+    unsafe extern "C" fn on_grab_error(_display: *mut xlib::Display, _event: *mut xlib::XErrorEvent) -> c_int {
+        GRAB_FAILED.store(true, Ordering::SeqCst);
+        0
+    }
+
+    unsafe {
+        let display = xlib::XOpenDisplay(ptr::null());
+
+        if display.is_null() {
+            return Err(anyhow!("Failed to open the X display"));
+        }
+
+        let root = xlib::XDefaultRootWindow(display);
+        let code = xlib::XKeysymToKeycode(display, keysym::XK_F8 as xlib::KeySym) as c_int;
+
+        if code == 0 {
+            xlib::XCloseDisplay(display);
+            return Err(anyhow!("F8 isn't on the keyboard map of the X server"));
+        }
+
+        // with any modifier, num lock or caps lock being on would otherwise keep F8 from being grabbed
+        GRAB_FAILED.store(false, Ordering::SeqCst);
+        let previous_handler = xlib::XSetErrorHandler(Some(on_grab_error));
+        xlib::XGrabKey(display, code, xlib::AnyModifier, root, xlib::False, xlib::GrabModeAsync, xlib::GrabModeAsync);
+        xlib::XSync(display, xlib::False);
+        xlib::XSetErrorHandler(previous_handler);
+
+        if GRAB_FAILED.load(Ordering::SeqCst) {
+            xlib::XCloseDisplay(display);
+            return Err(anyhow!("F8 is already a shortcut of another application"));
+        }
+
+        let mut pressed = false;
+
+        while !pressed && *waiting.lock().unwrap() {
+            while xlib::XPending(display) > 0 {
+                let mut event: xlib::XEvent = mem::zeroed();
+                xlib::XNextEvent(display, &mut event);
+
+                pressed |= event.get_type() == xlib::KeyPress;
+            }
+
+            if !pressed {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+
+        xlib::XUngrabKey(display, code, xlib::AnyModifier, root);
+        xlib::XFlush(display);
+        xlib::XCloseDisplay(display);
+
+        Ok(pressed)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn system_wait_for_f8(_waiting: &Mutex<bool>) -> Result<bool> {
+    Err(anyhow!("F8 outside of the window isn't supported on this system"))
+}
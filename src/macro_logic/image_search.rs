@@ -0,0 +1,571 @@
+// template matching over raw RGBA buffers, replaces autopilot's find_bitmap
+// because that one only knows RGB and can't skip the transparent parts of a template
+
+use image::RgbaImage;
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+
+/// pixels with alpha below this value are masked out and never compared
+pub const MASK_ALPHA_THRESHOLD: u8 = 128;
+
+/// how many template pixels are used to estimate the brightness offset in `MatchMode::ColorInsensitive`
+const BRIGHTNESS_SAMPLE_SIZE: usize = 32;
+
+/// side of the cells checked by the pre-pass, a cell that fails rules out COARSE_STEP * COARSE_STEP positions at once
+const COARSE_STEP: u32 = 4;
+
+/// what the matcher compares, the allowed difference applies to these values instead of raw colors
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchMode {
+    #[default]
+    Exact,              // RGB colors, same as before modes existed
+    Grayscale,          // brightness only, survives hue changes
+    ColorInsensitive,   // brightness with the difference in overall brightness removed, survives hover highlights
+    Edges               // strength of the brightness gradient, survives theme switches as long as the shapes stay the same
+}
+
+impl MatchMode {
+    fn channels(&self) -> usize {
+        match self {
+            MatchMode::Exact => 3,
+            _ => 1
+        }
+    }
+}
+
+/// an image converted into the values compared by a match mode, `channels` bytes per pixel
+struct FeatureImage {
+    width: u32,
+    height: u32,
+    channels: usize,
+    data: Vec<u8>
+}
+
+impl FeatureImage {
+    fn new(image: &RgbaImage, mode: MatchMode) -> Self {
+        let (width, height) = image.dimensions();
+
+        let data = match mode {
+            MatchMode::Exact => image.pixels().flat_map(|p| [p.0[0], p.0[1], p.0[2]]).collect(),
+            MatchMode::Grayscale | MatchMode::ColorInsensitive => image.pixels().map(|p| luma(p.0)).collect(),
+            MatchMode::Edges => {
+                let lumas: Vec<u8> = image.pixels().map(|p| luma(p.0)).collect();
+                sobel(&lumas, width, height)
+            },
+        };
+
+        FeatureImage { width, height, channels: mode.channels(), data }
+    }
+
+    fn value(&self, x: u32, y: u32) -> &[u8] {
+        let index = ((y as usize) * (self.width as usize) + x as usize) * self.channels;
+
+        &self.data[index..index + self.channels]
+    }
+
+    /// smallest and largest value of every channel in the COARSE_STEP x COARSE_STEP window that starts at each pixel,
+    /// windows that stick out of the image only cover the part that's inside
+    fn window_bounds(&self) -> (FeatureImage, FeatureImage) {
+        let row_len = self.width as usize * self.channels;
+
+        fn bounds(image: &FeatureImage, row_len: usize, pick: impl Fn(u8, u8) -> u8 + Copy + Sync) -> FeatureImage {
+            let mut data = image.data.clone();
+            let mut span = 1;
+
+            // every round doubles the covered window, first along x then along y
+            while span < COARSE_STEP as usize {
+                data = pool_columns(&data, row_len, span * image.channels, pick);
+                data = pool_rows(&data, row_len, span, pick);
+
+                span *= 2;
+            }
+
+            FeatureImage { width: image.width, height: image.height, channels: image.channels, data }
+        }
+
+        (bounds(self, row_len, u8::min), bounds(self, row_len, u8::max))
+    }
+}
+
+/// combines every value with the one `step` bytes further along the same row, values without a partner are kept as is
+fn pool_columns(data: &[u8], row_len: usize, step: usize, pick: impl Fn(u8, u8) -> u8 + Sync) -> Vec<u8> {
+    let mut res = data.to_vec();
+
+    res.par_chunks_mut(row_len).zip(data.par_chunks(row_len)).for_each(|(res, data)| {
+        if step < data.len() {
+            for (res, (a, b)) in res.iter_mut().zip(data.iter().zip(data[step..].iter())) {
+                *res = pick(*a, *b);
+            }
+        }
+    });
+
+    res
+}
+
+/// combines every row with the one `rows` further down, rows without a partner are kept as is
+fn pool_rows(data: &[u8], row_len: usize, rows: usize, pick: impl Fn(u8, u8) -> u8 + Sync) -> Vec<u8> {
+    let mut res = data.to_vec();
+    let offset = rows * row_len;
+
+    res.par_chunks_mut(row_len).enumerate().for_each(|(y, res)| {
+        let start = y * row_len;
+
+        if start + offset < data.len() {
+            let (a, b) = (&data[start..start + row_len], &data[start + offset..start + offset + row_len]);
+
+            for (res, (a, b)) in res.iter_mut().zip(a.iter().zip(b.iter())) {
+                *res = pick(*a, *b);
+            }
+        }
+    });
+
+    res
+}
+
+struct TemplatePixel {
+    x: u32,
+    y: u32,
+    value: [u8; 3]      // only the first `channels` values are used
+}
+
+/// a template prepared for searching, can be reused for every screenshot of a waiting step
+pub struct Template {
+    mode: MatchMode,
+    width: u32,
+    height: u32,
+    pixels: Vec<TemplatePixel>,
+    brightness_sample: Vec<usize>
+}
+
+impl Template {
+    pub fn new(image: &RgbaImage, mode: MatchMode) -> Self {
+        let features = FeatureImage::new(image, mode);
+        let (width, height) = image.dimensions();
+
+        let mut pixels: Vec<TemplatePixel> = image.enumerate_pixels()
+            .filter(|(x, y, _)| {
+                match mode {
+                    // gradients at the border and next to the mask depend on pixels we know nothing about
+                    MatchMode::Edges => {
+                        *x > 0 && *y > 0 && *x + 1 < width && *y + 1 < height &&
+                        (*y - 1..=*y + 1).all(|ny| (*x - 1..=*x + 1).all(|nx| is_opaque(image.get_pixel(nx, ny).0[3])))
+                    },
+                    _ => is_opaque(image.get_pixel(*x, *y).0[3])
+                }
+            })
+            .map(|(x, y, _)| {
+                let mut value = [0; 3];
+                value[..features.channels].copy_from_slice(features.value(x, y));
+
+                TemplatePixel { x, y, value }
+            })
+            .collect();
+
+        // pixels that stand out from the rest of the template are checked first, so wrong positions fail quicker
+        let channels = features.channels;
+        let mean: Vec<i32> = (0..channels).map(|c| {
+            pixels.iter().map(|p| p.value[c] as i32).sum::<i32>() / (pixels.len().max(1) as i32)
+        }).collect();
+
+        pixels.sort_by_cached_key(|p| {
+            let distance: i32 = (0..channels).map(|c| (p.value[c] as i32 - mean[c]).pow(2)).sum();
+            std::cmp::Reverse(distance)
+        });
+
+        let sample_step = (pixels.len() / BRIGHTNESS_SAMPLE_SIZE).max(1);
+        let brightness_sample = (0..pixels.len()).step_by(sample_step).collect();
+
+        Template { mode, width, height, pixels, brightness_sample }
+    }
+
+    /// true if there is no pixel left to compare, e.g. the image is fully transparent
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// largest squared difference of a single pixel between the template and the screen at (x, y),
+    /// or None as soon as a pixel goes over `max_diff_sq`
+    fn difference_at(&self, screen: &FeatureImage, x: u32, y: u32, max_diff_sq: i32) -> Option<i32> {
+        let offset = match self.mode {
+            MatchMode::ColorInsensitive => {
+                let total: i32 = self.brightness_sample.iter().map(|i| {
+                    let pixel = &self.pixels[*i];
+                    screen.value(x + pixel.x, y + pixel.y)[0] as i32 - pixel.value[0] as i32
+                }).sum();
+
+                total / self.brightness_sample.len() as i32
+            },
+            _ => 0
+        };
+
+        let mut worst = 0;
+
+        for pixel in self.pixels.iter() {
+            let screen_value = screen.value(x + pixel.x, y + pixel.y);
+
+            let diff_sq: i32 = screen_value.iter().zip(pixel.value.iter()).map(|(screen_value, template_value)| {
+                let diff = *screen_value as i32 - *template_value as i32 - offset;
+                diff * diff
+            }).sum();
+
+            if diff_sq > max_diff_sq {
+                return None;
+            }
+
+            worst = worst.max(diff_sq);
+        }
+
+        Some(worst)
+    }
+
+    /// true if no position inside the cell that starts at (x, y) can match, based only on the window bounds of the screen
+    fn cell_rejected(&self, min: &FeatureImage, max: &FeatureImage, x: u32, y: u32, max_diff_sq: i32) -> bool {
+        self.pixels.iter().any(|pixel| {
+            let (lows, highs) = (min.value(x + pixel.x, y + pixel.y), max.value(x + pixel.x, y + pixel.y));
+
+            let gap_sq: i32 = lows.iter().zip(highs.iter()).zip(pixel.value.iter()).map(|((low, high), value)| {
+                let gap = (*low as i32 - *value as i32).max(*value as i32 - *high as i32).max(0);
+                gap * gap
+            }).sum();
+
+            gap_sq > max_diff_sq
+        })
+    }
+}
+
+pub fn is_opaque(alpha: u8) -> bool {
+    alpha >= MASK_ALPHA_THRESHOLD
+}
+
+pub fn has_opaque_pixels(template: &RgbaImage) -> bool {
+    template.pixels().any(|p| is_opaque(p.0[3]))
+}
+
+/// searches `screen` top to bottom, left to right, for `template` and returns the top left corner of the first match
+///
+/// `allowed_diff` works the same way autopilot's tolerance did - 0.0 is an exact match, 1.0 matches anything,
+/// and a single compared pixel further away than that fails the whole position
+pub fn find_image(screen: &RgbaImage, template: &Template, allowed_diff: f32) -> Option<(u32, u32)> {
+    if template.is_empty() || template.width > screen.width() || template.height > screen.height() {
+        return None;
+    }
+
+    let screen = FeatureImage::new(screen, template.mode);
+    let max_diff_sq = max_diff_sq(allowed_diff, screen.channels);
+
+    let last_x = screen.width - template.width;
+    let last_y = screen.height - template.height;
+
+    // the brightness offset changes from position to position, so the window bounds can't rule anything out
+    if template.mode == MatchMode::ColorInsensitive {
+        return (0..=last_y).into_par_iter().find_map_first(|y| {
+            (0..=last_x).find(|x| template.difference_at(&screen, *x, y, max_diff_sq).is_some()).map(|x| (x, y))
+        });
+    }
+
+    let (min, max) = screen.window_bounds();
+
+    (0..=last_y / COARSE_STEP).into_par_iter().find_map_first(|cell_y| {
+        let cell_y = cell_y * COARSE_STEP;
+
+        // the first match of a row of cells isn't necessarily in the first cell that passes,
+        // a cell further right can have a match on an earlier row
+        (0..=last_x / COARSE_STEP)
+            .map(|cell_x| cell_x * COARSE_STEP)
+            .filter(|cell_x| !template.cell_rejected(&min, &max, *cell_x, cell_y, max_diff_sq))
+            .flat_map(|cell_x| {
+                (cell_y..(cell_y + COARSE_STEP).min(last_y + 1)).flat_map(move |y| {
+                    (cell_x..(cell_x + COARSE_STEP).min(last_x + 1)).map(move |x| (x, y))
+                })
+            })
+            .filter(|(x, y)| template.difference_at(&screen, *x, *y, max_diff_sq).is_some())
+            .min_by_key(|(x, y)| (*y, *x))
+    })
+}
+
+/// a position the template was compared at and how different the screen was there
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate {
+    pub x: u32,
+    pub y: u32,
+    pub difference: f32,    // the lowest allowed difference this position matches with, 0.0 is a perfect match
+    worst_sq: i32,
+    channels: usize
+}
+
+impl Candidate {
+    fn new(x: u32, y: u32, worst_sq: i32, channels: usize) -> Self {
+        let difference = ((worst_sq as f32 / channels as f32).sqrt() / 255.0).min(1.0);
+
+        Candidate { x, y, difference, worst_sq, channels }
+    }
+
+    /// exactly what `find_image` decides for this position
+    pub fn matches(&self, allowed_diff: f32) -> bool {
+        self.worst_sq <= max_diff_sq(allowed_diff, self.channels)
+    }
+
+    /// the same candidate with its position moved, for a search that only covered a part of the screen
+    pub fn moved_by(self, x: u32, y: u32) -> Self {
+        Candidate { x: self.x + x, y: self.y + y, ..self }
+    }
+}
+
+/// the `count` positions that come closest to matching, best first, for finding out why a template is or isn't found
+///
+/// the candidates don't overlap each other, otherwise the positions right next to the best one would take all the places
+pub fn best_candidates(screen: &RgbaImage, template: &Template, count: usize) -> Vec<Candidate> {
+    if template.is_empty() || template.width > screen.width() || template.height > screen.height() {
+        return Vec::new();
+    }
+
+    let screen = FeatureImage::new(screen, template.mode);
+    let (last_x, last_y) = (screen.width - template.width, screen.height - template.height);
+
+    // the best position of every template sized cell, a position can stop being compared once it's worse than that
+    let cells: Vec<(u32, u32)> = (0..=last_y).step_by(template.height as usize)
+        .flat_map(|y| (0..=last_x).step_by(template.width as usize).map(move |x| (x, y)))
+        .collect();
+
+    let mut cell_bests: Vec<Candidate> = cells.into_par_iter().filter_map(|(cell_x, cell_y)| {
+        let mut best: Option<(u32, u32, i32)> = None;
+
+        for y in cell_y..(cell_y + template.height).min(last_y + 1) {
+            for x in cell_x..(cell_x + template.width).min(last_x + 1) {
+                let limit = best.map_or(i32::MAX, |(_, _, worst_sq)| worst_sq - 1);
+
+                if let Some(worst_sq) = template.difference_at(&screen, x, y, limit) {
+                    best = Some((x, y, worst_sq));
+                }
+            }
+        }
+
+        best.map(|(x, y, worst_sq)| Candidate::new(x, y, worst_sq, screen.channels))
+    }).collect();
+
+    cell_bests.sort_by_key(|candidate| (candidate.worst_sq, candidate.y, candidate.x));
+
+    let mut res: Vec<Candidate> = Vec::with_capacity(count);
+
+    for candidate in cell_bests {
+        if res.len() >= count {
+            break;
+        }
+
+        let overlaps = res.iter().any(|picked| {
+            picked.x.abs_diff(candidate.x) < template.width && picked.y.abs_diff(candidate.y) < template.height
+        });
+
+        if !overlaps {
+            res.push(candidate);
+        }
+    }
+
+    res
+}
+
+/// the difference of every channel can be up to 255 * allowed_diff
+fn max_diff_sq(allowed_diff: f32, channels: usize) -> i32 {
+    let max_diff = allowed_diff.clamp(0.0, 1.0) * 255.0;
+
+    (max_diff * max_diff * channels as f32) as i32
+}
+
+fn luma(rgba: [u8; 4]) -> u8 {
+    ((2126 * rgba[0] as u32 + 7152 * rgba[1] as u32 + 722 * rgba[2] as u32) / 10000) as u8
+}
+
+/// gradient magnitude of a grayscale buffer, scaled back into 0..=255, border pixels reuse their nearest neighbour
+fn sobel(lumas: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let (width, height) = (width as i64, height as i64);
+    let at = |x: i64, y: i64| lumas[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize] as i32;
+
+    let mut res = Vec::with_capacity(lumas.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let gx = (at(x + 1, y - 1) + 2 * at(x + 1, y) + at(x + 1, y + 1)) - (at(x - 1, y - 1) + 2 * at(x - 1, y) + at(x - 1, y + 1));
+            let gy = (at(x - 1, y + 1) + 2 * at(x, y + 1) + at(x + 1, y + 1)) - (at(x - 1, y - 1) + 2 * at(x, y - 1) + at(x + 1, y - 1));
+
+            // largest possible magnitude is 4 * sqrt(2) * 255
+            let magnitude = (((gx * gx + gy * gy) as f32).sqrt() / (4.0 * std::f32::consts::SQRT_2)).round();
+            res.push(magnitude.min(255.0) as u8);
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use image::{RgbaImage, Rgba, GenericImage};
+
+    use super::{find_image, best_candidates, has_opaque_pixels, MatchMode, Template, FeatureImage};
+
+    /// a white 20x10 screen with a 3x3 square of `color` at x 12, y 4, the other modules that search screens test on it too
+    pub fn screen_with_square(color: [u8; 3]) -> RgbaImage {
+        let mut screen = RgbaImage::from_pixel(20, 10, Rgba([255, 255, 255, 255]));
+
+        for y in 4..7 {
+            for x in 12..15 {
+                screen.put_pixel(x, y, Rgba([color[0], color[1], color[2], 255]));
+            }
+        }
+
+        screen
+    }
+
+    /// 3x3 square of `square` color with a one pixel border of `border` color around it
+    fn bordered_square(square: [u8; 3], border: [u8; 3]) -> RgbaImage {
+        let mut template = RgbaImage::from_pixel(5, 5, Rgba([border[0], border[1], border[2], 255]));
+
+        for y in 1..4 {
+            for x in 1..4 {
+                template.put_pixel(x, y, Rgba([square[0], square[1], square[2], 255]));
+            }
+        }
+
+        template
+    }
+
+    #[test]
+    fn finds_exact_match() {
+        let template = RgbaImage::from_pixel(3, 3, Rgba([200, 0, 0, 255]));
+
+        assert_eq!(find_image(&screen_with_square([200, 0, 0]), &Template::new(&template, MatchMode::Exact), 0.0), Some((12, 4)));
+    }
+
+    #[test]
+    fn transparent_pixels_are_ignored() {
+        // a black frame around the red square on screen, the template's inside is transparent and doesn't match
+        let mut screen = screen_with_square([200, 0, 0]);
+
+        for y in 3..8 {
+            for x in 11..16 {
+                if x == 11 || x == 15 || y == 3 || y == 7 {
+                    screen.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+                }
+            }
+        }
+
+        let mut template = RgbaImage::from_pixel(5, 5, Rgba([0, 0, 0, 255]));
+
+        for y in 1..4 {
+            for x in 1..4 {
+                template.put_pixel(x, y, Rgba([0, 0, 255, 0]));
+            }
+        }
+
+        assert_eq!(find_image(&screen, &Template::new(&template, MatchMode::Exact), 0.0), Some((11, 3)));
+    }
+
+    #[test]
+    fn allowed_difference_is_respected() {
+        let template = Template::new(&RgbaImage::from_pixel(3, 3, Rgba([190, 10, 0, 255])), MatchMode::Exact);
+
+        assert_eq!(find_image(&screen_with_square([200, 0, 0]), &template, 0.0), None);
+        assert_eq!(find_image(&screen_with_square([200, 0, 0]), &template, 0.05), Some((12, 4)));
+    }
+
+    #[test]
+    fn fully_transparent_template_never_matches() {
+        let image = RgbaImage::from_pixel(3, 3, Rgba([200, 0, 0, 0]));
+        let template = Template::new(&image, MatchMode::Exact);
+
+        assert!(!has_opaque_pixels(&image));
+        assert!(template.is_empty());
+        assert_eq!(find_image(&screen_with_square([200, 0, 0]), &template, 1.0), None);
+    }
+
+    #[test]
+    fn grayscale_ignores_hue() {
+        // about the same brightness as the red square, but green
+        let template = RgbaImage::from_pixel(3, 3, Rgba([0, 59, 0, 255]));
+
+        assert_eq!(find_image(&screen_with_square([200, 0, 0]), &Template::new(&template, MatchMode::Exact), 0.05), None);
+        assert_eq!(find_image(&screen_with_square([200, 0, 0]), &Template::new(&template, MatchMode::Grayscale), 0.01), Some((12, 4)));
+    }
+
+    #[test]
+    fn color_insensitive_ignores_brightness_shift() {
+        let mut screen = RgbaImage::from_pixel(20, 10, Rgba([100, 100, 100, 255]));
+        screen.copy_from(&bordered_square([150, 150, 150], [100, 100, 100]), 6, 2);
+
+        // the hovered version of the button, everything 40 levels brighter
+        let template = bordered_square([190, 190, 190], [140, 140, 140]);
+
+        assert_eq!(find_image(&screen, &Template::new(&template, MatchMode::Grayscale), 0.05), None);
+        assert_eq!(find_image(&screen, &Template::new(&template, MatchMode::ColorInsensitive), 0.01), Some((6, 2)));
+    }
+
+    #[test]
+    fn edges_survive_inverted_colors() {
+        let mut screen = RgbaImage::from_pixel(20, 10, Rgba([255, 255, 255, 255]));
+        screen.copy_from(&bordered_square([0, 0, 0], [255, 255, 255]), 9, 3);
+
+        // dark theme version of the same square
+        let template = bordered_square([255, 255, 255], [0, 0, 0]);
+
+        assert_eq!(find_image(&screen, &Template::new(&template, MatchMode::ColorInsensitive), 0.1), None);
+        assert_eq!(find_image(&screen, &Template::new(&template, MatchMode::Edges), 0.01), Some((9, 3)));
+    }
+
+    #[test]
+    fn prepass_finds_the_same_match_as_a_full_scan() {
+        // noisy screen so that the window bounds are loose, with a couple of copies of the template on it
+        let mut seed: u32 = 12345;
+        let screen = RgbaImage::from_fn(97, 61, |_, _| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let value = (seed >> 16) as u8;
+            Rgba([value, value / 2, 255 - value, 255])
+        });
+
+        let mut template = RgbaImage::new(7, 5);
+        for (x, y, pixel) in template.enumerate_pixels_mut() {
+            *pixel = *screen.get_pixel(x + 41, y + 23);
+        }
+
+        let mut screen_with_copy = screen.clone();
+        screen_with_copy.copy_from(&template, 70, 50);
+
+        for mode in [MatchMode::Exact, MatchMode::Grayscale, MatchMode::Edges] {
+            for allowed_diff in [0.0, 0.05, 0.2] {
+                let prepared = Template::new(&template, mode);
+                let features = FeatureImage::new(&screen_with_copy, mode);
+                let max_diff = allowed_diff * 255.0;
+                let max_diff_sq = (max_diff * max_diff * features.channels as f32) as i32;
+
+                let full_scan = (0..=(screen_with_copy.height() - 5)).flat_map(|y| (0..=(screen_with_copy.width() - 7)).map(move |x| (x, y)))
+                    .find(|(x, y)| prepared.difference_at(&features, *x, *y, max_diff_sq).is_some());
+
+                assert!(full_scan.is_some());
+                assert_eq!(find_image(&screen_with_copy, &prepared, allowed_diff), full_scan, "{:?} {}", mode, allowed_diff);
+            }
+        }
+    }
+
+    #[test]
+    fn candidates_are_ranked_and_apart() {
+        // the exact square and a slightly darker copy of it further left
+        let mut screen = screen_with_square([200, 0, 0]);
+
+        for y in 4..7 {
+            for x in 2..5 {
+                screen.put_pixel(x, y, Rgba([190, 0, 0, 255]));
+            }
+        }
+
+        let template = Template::new(&RgbaImage::from_pixel(3, 3, Rgba([200, 0, 0, 255])), MatchMode::Exact);
+        let candidates = best_candidates(&screen, &template, 3);
+
+        assert_eq!(candidates.len(), 3);
+        assert_eq!((candidates[0].x, candidates[0].y, candidates[0].difference), (12, 4, 0.0));
+        assert_eq!((candidates[1].x, candidates[1].y), (2, 4));
+        assert!(candidates[1].difference > 0.0 && candidates[1].difference < candidates[2].difference);
+
+        // the score agrees with the search
+        assert!(candidates[1].matches(0.05) && !candidates[1].matches(0.0));
+        assert_eq!(find_image(&screen, &template, 0.0), Some((12, 4)));
+        assert_eq!(find_image(&screen, &Template::new(&RgbaImage::from_pixel(3, 3, Rgba([190, 0, 0, 255])), MatchMode::Exact), 0.0), Some((2, 4)));
+    }
+}
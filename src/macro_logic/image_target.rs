@@ -0,0 +1,176 @@
+use anyhow::{Result, anyhow};
+use image::{RgbaImage, GenericImageView};
+
+use super::anchor::Anchor;
+use super::image_search::{self, Candidate, MatchMode, Template};
+use super::macro_base::{ClickPoint, ImageSlot};
+
+/// what ClickImage and MoveToImage search for and where on the match the mouse goes
+#[derive(Clone, Debug, Default)]
+pub struct ImageTarget {
+    pub images: Vec<RgbaImage>,     // alternatives - the first one found is used
+    pub point: ClickPoint,          // where on the found image the mouse goes
+    pub allowed_diff: f32,
+    pub mode: MatchMode,            // what is compared
+    pub anchor: Option<Anchor>      // image the search is limited around
+}
+
+/// the templates of a target, made once before it's searched for
+pub struct TargetTemplates {
+    images: Vec<Template>,
+    anchor: Option<Template>
+}
+
+impl ImageTarget {
+    pub fn prepare(&self) -> Result<TargetTemplates> {
+        // without images of its own the step targets the anchor itself
+        if self.images.is_empty() && self.anchor.is_none() {
+            return Err(anyhow!("Missing image data"));
+        }
+
+        Ok(TargetTemplates {
+            images: self.images.iter().map(|image| prepare_template(image, &self.mode)).collect::<Result<Vec<Template>>>()?,
+            anchor: self.anchor.as_ref().map(Anchor::prepare).transpose()?
+        })
+    }
+
+    /// the image in `slot`, for steps that have their anchor as their target too
+    pub fn image(&self, slot: ImageSlot) -> Option<&RgbaImage> {
+        match slot {
+            ImageSlot::Template(index) => self.images.get(index),
+            ImageSlot::Anchor => self.anchor.as_ref()?.image.as_ref(),
+        }
+    }
+
+    /// where the step finds its target on the screen and which of its images is there,
+    /// alternatives are tried in order, so the most likely look of the button should be the first one
+    pub fn locate(&self, screen: &RgbaImage, templates: &TargetTemplates) -> Option<((u32, u32), ImageSlot)> {
+        let anchor = match (&self.anchor, &templates.anchor) {
+            (Some(anchor), Some(anchor_template)) => Some((anchor, anchor.find(screen, anchor_template)?)),
+            _ => None,
+        };
+
+        if self.images.is_empty() {
+            return anchor.map(|(_, anchor_position)| (anchor_position, ImageSlot::Anchor));
+        }
+
+        templates.images.iter().enumerate().find_map(|(index, template)| {
+            let position = match anchor {
+                Some((anchor, anchor_position)) => anchor.find_in_region(screen, anchor_position, template, self.allowed_diff),
+                None => image_search::find_image(screen, template, self.allowed_diff),
+            };
+
+            position.map(|position| (position, ImageSlot::Template(index)))
+        })
+    }
+
+    /// the part of the screen the images are searched in as x, y, width, height, all of it for steps without an anchor,
+    /// None when the anchor isn't on the screen
+    pub fn search_region(&self, screen: &RgbaImage, templates: &TargetTemplates) -> Option<(u32, u32, u32, u32)> {
+        match (&self.anchor, &templates.anchor) {
+            (Some(anchor), Some(anchor_template)) => anchor.region(anchor.find(screen, anchor_template)?, screen.width(), screen.height()),
+            _ => Some((0, 0, screen.width(), screen.height())),
+        }
+    }
+
+    /// the best places of every image inside a region from `search_region`, with positions on the whole screen
+    pub fn best_candidates(&self, screen: &RgbaImage, templates: &TargetTemplates, region: (u32, u32, u32, u32), count: usize) -> Vec<Vec<Candidate>> {
+        let (left, top, width, height) = region;
+        let region = screen.view(left, top, width, height).to_image();
+
+        templates.images.iter().map(|template| {
+            image_search::best_candidates(&region, template, count).into_iter()
+                .map(|candidate| candidate.moved_by(left, top))
+                .collect()
+        }).collect()
+    }
+}
+
+pub fn prepare_template(img_data: &RgbaImage, mode: &MatchMode) -> Result<Template> {
+    let template = Template::new(img_data, *mode);
+
+    if template.is_empty() {
+        return Err(anyhow!("Image has no pixels to search for, it's either fully transparent or too small for this match mode"));
+    }
+
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::ImageTarget;
+    use crate::macro_logic::anchor::Anchor;
+    use crate::macro_logic::image_search::MatchMode;
+    use crate::macro_logic::macro_base::ImageSlot;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+
+    fn dot(color: Rgba<u8>) -> RgbaImage {
+        RgbaImage::from_pixel(1, 1, color)
+    }
+
+    /// red dots left and right, a green one between them to anchor the search on
+    fn screen() -> RgbaImage {
+        let mut screen = RgbaImage::from_pixel(40, 10, Rgba([0, 0, 0, 255]));
+        screen.put_pixel(5, 5, RED);
+        screen.put_pixel(20, 5, GREEN);
+        screen.put_pixel(30, 5, RED);
+        screen.put_pixel(35, 2, BLUE);
+
+        screen
+    }
+
+    fn target(images: Vec<RgbaImage>, anchor: Option<Anchor>) -> ImageTarget {
+        ImageTarget { images, mode: MatchMode::Exact, anchor, ..Default::default() }
+    }
+
+    fn green_anchor(x: i32) -> Anchor {
+        Anchor { image: Some(dot(GREEN)), mode: MatchMode::Exact, x, y: -5, width: 15, height: 10, ..Default::default() }
+    }
+
+    #[test]
+    fn first_alternative_found_is_used() {
+        let target = target(vec![dot(BLUE), dot(RED)], None);
+        let templates = target.prepare().unwrap();
+
+        // the red dots are higher up on the screen, but the blue alternative comes first
+        assert_eq!(target.locate(&screen(), &templates), Some(((35, 2), ImageSlot::Template(0))));
+    }
+
+    #[test]
+    fn anchor_limits_the_search() {
+        let target = target(vec![dot(RED)], Some(green_anchor(5)));
+        let templates = target.prepare().unwrap();
+        let region = target.search_region(&screen(), &templates).unwrap();
+
+        assert_eq!(region, (25, 0, 15, 10));
+        assert_eq!(target.locate(&screen(), &templates), Some(((30, 5), ImageSlot::Template(0))));
+
+        let candidates = target.best_candidates(&screen(), &templates, region, 1);
+        assert_eq!((candidates[0][0].x, candidates[0][0].y), (30, 5));
+    }
+
+    #[test]
+    fn anchor_alone_is_the_target() {
+        let target = target(Vec::new(), Some(green_anchor(0)));
+        let templates = target.prepare().unwrap();
+
+        assert_eq!(target.locate(&screen(), &templates), Some(((20, 5), ImageSlot::Anchor)));
+    }
+
+    #[test]
+    fn missing_anchor_finds_nothing() {
+        let mut screen = screen();
+        screen.put_pixel(20, 5, Rgba([0, 0, 0, 255]));
+
+        let target = target(vec![dot(RED)], Some(green_anchor(5)));
+        let templates = target.prepare().unwrap();
+
+        assert_eq!(target.search_region(&screen, &templates), None);
+        assert_eq!(target.locate(&screen, &templates), None);
+    }
+}
@@ -0,0 +1,485 @@
+use anyhow::{Result, anyhow};
+use autopilot::key::{self, Flag, KeyCode};
+use serde::{Serialize, Deserialize};
+
+use super::EnumInterString;
+
+/// any key a step can press - autopilot only has names for part of the keyboard, the rest is sent
+/// through the system directly and characters go through the current keyboard layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Key {
+    // named like the KeyCode of autopilot, files from before other keys existed stored those
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    LeftArrow,
+    Control,
+    RightArrow,
+    DownArrow,
+    End,
+    UpArrow,
+    PageUp,
+    Alt,
+    Return,
+    PageDown,
+    Delete,
+    Home,
+    Escape,
+    Backspace,
+    Meta,
+    CapsLock,
+    Shift,
+    Tab,
+    Space,
+    // autopilot has no code for these
+    Insert,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    NumLock,
+    Menu,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadMultiply,
+    NumpadDivide,
+    NumpadDecimal,
+    NumpadEnter,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    MediaPlayPause,
+    MediaNext,
+    MediaPrevious,
+    MediaStop,
+    Character(char)     // letters, digits and punctuation, always lowercase
+}
+
+impl Key {
+    /// characters that are offered in the key picker, any other one can still be typed in
+    pub const CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyz0123456789`-=[]\\;',./";
+
+    pub fn tap(&self, flags: &[Flag]) -> Result<()> {
+        self.toggle(true, flags)?;
+        self.toggle(false, flags)
+    }
+
+    /// presses or releases the key, the modifiers are pressed before it and released after it
+    pub fn toggle(&self, down: bool, flags: &[Flag]) -> Result<()> {
+        if let Some(code) = self.autopilot_code() {
+            key::toggle(&key::Code(code), down, flags, 0);
+            return Ok(());
+        }
+
+        if let Key::Character(character) = self {
+            key::toggle(&key::Character(*character), down, flags, 0);
+            return Ok(());
+        }
+
+        let modifiers: Vec<KeyCode> = flags.iter().filter_map(modifier_code).collect();
+
+        if down {
+            for modifier in &modifiers {
+                key::toggle(&key::Code(*modifier), true, &[], 0);
+            }
+        }
+
+        let res = system_toggle(*self, down);
+
+        if !down {
+            for modifier in modifiers.iter().rev() {
+                key::toggle(&key::Code(*modifier), false, &[], 0);
+            }
+        }
+
+        res
+    }
+
+    /// the code autopilot knows the key by, None for characters and the keys it has no name for
+    pub fn autopilot_code(&self) -> Option<KeyCode> {
+        Some(match self {
+            Key::F1 => KeyCode::F1,
+            Key::F2 => KeyCode::F2,
+            Key::F3 => KeyCode::F3,
+            Key::F4 => KeyCode::F4,
+            Key::F5 => KeyCode::F5,
+            Key::F6 => KeyCode::F6,
+            Key::F7 => KeyCode::F7,
+            Key::F8 => KeyCode::F8,
+            Key::F9 => KeyCode::F9,
+            Key::F10 => KeyCode::F10,
+            Key::F11 => KeyCode::F11,
+            Key::F12 => KeyCode::F12,
+            Key::F13 => KeyCode::F13,
+            Key::F14 => KeyCode::F14,
+            Key::F15 => KeyCode::F15,
+            Key::F16 => KeyCode::F16,
+            Key::F17 => KeyCode::F17,
+            Key::F18 => KeyCode::F18,
+            Key::F19 => KeyCode::F19,
+            Key::F20 => KeyCode::F20,
+            Key::F21 => KeyCode::F21,
+            Key::F22 => KeyCode::F22,
+            Key::F23 => KeyCode::F23,
+            Key::F24 => KeyCode::F24,
+            Key::LeftArrow => KeyCode::LeftArrow,
+            Key::Control => KeyCode::Control,
+            Key::RightArrow => KeyCode::RightArrow,
+            Key::DownArrow => KeyCode::DownArrow,
+            Key::End => KeyCode::End,
+            Key::UpArrow => KeyCode::UpArrow,
+            Key::PageUp => KeyCode::PageUp,
+            Key::Alt => KeyCode::Alt,
+            Key::Return => KeyCode::Return,
+            Key::PageDown => KeyCode::PageDown,
+            Key::Delete => KeyCode::Delete,
+            Key::Home => KeyCode::Home,
+            Key::Escape => KeyCode::Escape,
+            Key::Backspace => KeyCode::Backspace,
+            Key::Meta => KeyCode::Meta,
+            Key::CapsLock => KeyCode::CapsLock,
+            Key::Shift => KeyCode::Shift,
+            Key::Tab => KeyCode::Tab,
+            Key::Space => KeyCode::Space,
+            _ => return None
+        })
+    }
+}
+
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Self {
+        match code {
+            KeyCode::F1 => Key::F1,
+            KeyCode::F2 => Key::F2,
+            KeyCode::F3 => Key::F3,
+            KeyCode::F4 => Key::F4,
+            KeyCode::F5 => Key::F5,
+            KeyCode::F6 => Key::F6,
+            KeyCode::F7 => Key::F7,
+            KeyCode::F8 => Key::F8,
+            KeyCode::F9 => Key::F9,
+            KeyCode::F10 => Key::F10,
+            KeyCode::F11 => Key::F11,
+            KeyCode::F12 => Key::F12,
+            KeyCode::F13 => Key::F13,
+            KeyCode::F14 => Key::F14,
+            KeyCode::F15 => Key::F15,
+            KeyCode::F16 => Key::F16,
+            KeyCode::F17 => Key::F17,
+            KeyCode::F18 => Key::F18,
+            KeyCode::F19 => Key::F19,
+            KeyCode::F20 => Key::F20,
+            KeyCode::F21 => Key::F21,
+            KeyCode::F22 => Key::F22,
+            KeyCode::F23 => Key::F23,
+            KeyCode::F24 => Key::F24,
+            KeyCode::LeftArrow => Key::LeftArrow,
+            KeyCode::Control => Key::Control,
+            KeyCode::RightArrow => Key::RightArrow,
+            KeyCode::DownArrow => Key::DownArrow,
+            KeyCode::End => Key::End,
+            KeyCode::UpArrow => Key::UpArrow,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::Alt => Key::Alt,
+            KeyCode::Return => Key::Return,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Home => Key::Home,
+            KeyCode::Escape => Key::Escape,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Meta => Key::Meta,
+            KeyCode::CapsLock => Key::CapsLock,
+            KeyCode::Shift => Key::Shift,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::Space => Key::Space,
+        }
+    }
+}
+
+/// the key held down for a modifier flag, Help has none
+pub fn modifier_code(flag: &Flag) -> Option<KeyCode> {
+    match flag {
+        Flag::Shift => Some(KeyCode::Shift),
+        Flag::Control => Some(KeyCode::Control),
+        Flag::Alt => Some(KeyCode::Alt),
+        Flag::Meta => Some(KeyCode::Meta),
+        Flag::Help => None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn system_toggle(key: Key, down: bool) -> Result<()> {
+    use std::mem;
+    use winapi::um::winuser::{self, INPUT, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP};
+
+    let code = match key {
+        Key::Insert => winuser::VK_INSERT,
+        Key::PrintScreen => winuser::VK_SNAPSHOT,
+        Key::ScrollLock => winuser::VK_SCROLL,
+        Key::Pause => winuser::VK_PAUSE,
+        Key::NumLock => winuser::VK_NUMLOCK,
+        Key::Menu => winuser::VK_APPS,
+        Key::Numpad0 => winuser::VK_NUMPAD0,
+        Key::Numpad1 => winuser::VK_NUMPAD1,
+        Key::Numpad2 => winuser::VK_NUMPAD2,
+        Key::Numpad3 => winuser::VK_NUMPAD3,
+        Key::Numpad4 => winuser::VK_NUMPAD4,
+        Key::Numpad5 => winuser::VK_NUMPAD5,
+        Key::Numpad6 => winuser::VK_NUMPAD6,
+        Key::Numpad7 => winuser::VK_NUMPAD7,
+        Key::Numpad8 => winuser::VK_NUMPAD8,
+        Key::Numpad9 => winuser::VK_NUMPAD9,
+        Key::NumpadAdd => winuser::VK_ADD,
+        Key::NumpadSubtract => winuser::VK_SUBTRACT,
+        Key::NumpadMultiply => winuser::VK_MULTIPLY,
+        Key::NumpadDivide => winuser::VK_DIVIDE,
+        Key::NumpadDecimal => winuser::VK_DECIMAL,
+        Key::NumpadEnter => winuser::VK_RETURN,
+        Key::VolumeUp => winuser::VK_VOLUME_UP,
+        Key::VolumeDown => winuser::VK_VOLUME_DOWN,
+        Key::VolumeMute => winuser::VK_VOLUME_MUTE,
+        Key::MediaPlayPause => winuser::VK_MEDIA_PLAY_PAUSE,
+        Key::MediaNext => winuser::VK_MEDIA_NEXT_TRACK,
+        Key::MediaPrevious => winuser::VK_MEDIA_PREV_TRACK,
+        Key::MediaStop => winuser::VK_MEDIA_STOP,
+        _ => return Err(anyhow!("{:?} has no virtual key code", key)),
+    };
+
+    // the enter of the numpad is told apart from the main one only by this flag
+    let mut flags = if key == Key::NumpadEnter { KEYEVENTF_EXTENDEDKEY } else { 0 };
+
+    if !down {
+        flags |= KEYEVENTF_KEYUP;
+    }
+
+    unsafe {
+        let mut input = INPUT { type_: INPUT_KEYBOARD, u: mem::zeroed() };
+        *input.u.ki_mut() = KEYBDINPUT { wVk: code as u16, wScan: 0, dwFlags: flags, time: 0, dwExtraInfo: 0 };
+
+        match winuser::SendInput(1, &mut input, mem::size_of::<INPUT>() as i32) {
+            0 => Err(anyhow!("Failed to send {:?}", key)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn system_toggle(key: Key, down: bool) -> Result<()> {
+    use std::ptr;
+    use x11::{keysym, xlib, xtest};
+
+    let keysym = match key {
+        Key::Insert => keysym::XK_Insert,
+        Key::PrintScreen => keysym::XK_Print,
+        Key::ScrollLock => keysym::XK_Scroll_Lock,
+        Key::Pause => keysym::XK_Pause,
+        Key::NumLock => keysym::XK_Num_Lock,
+        Key::Menu => keysym::XK_Menu,
+        Key::Numpad0 => keysym::XK_KP_0,
+        Key::Numpad1 => keysym::XK_KP_1,
+        Key::Numpad2 => keysym::XK_KP_2,
+        Key::Numpad3 => keysym::XK_KP_3,
+        Key::Numpad4 => keysym::XK_KP_4,
+        Key::Numpad5 => keysym::XK_KP_5,
+        Key::Numpad6 => keysym::XK_KP_6,
+        Key::Numpad7 => keysym::XK_KP_7,
+        Key::Numpad8 => keysym::XK_KP_8,
+        Key::Numpad9 => keysym::XK_KP_9,
+        Key::NumpadAdd => keysym::XK_KP_Add,
+        Key::NumpadSubtract => keysym::XK_KP_Subtract,
+        Key::NumpadMultiply => keysym::XK_KP_Multiply,
+        Key::NumpadDivide => keysym::XK_KP_Divide,
+        Key::NumpadDecimal => keysym::XK_KP_Decimal,
+        Key::NumpadEnter => keysym::XK_KP_Enter,
+        Key::VolumeUp => 0x1008ff13,
+        Key::VolumeDown => 0x1008ff11,
+        Key::VolumeMute => 0x1008ff12,
+        Key::MediaPlayPause => 0x1008ff14,
+        Key::MediaNext => 0x1008ff17,
+        Key::MediaPrevious => 0x1008ff16,
+        Key::MediaStop => 0x1008ff15,
+        _ => return Err(anyhow!("{:?} has no X keysym", key)),
+    };
+
+    unsafe {
+        let display = xlib::XOpenDisplay(ptr::null());
+
+        if display.is_null() {
+            return Err(anyhow!("Failed to open the X display"));
+        }
+
+        let code = xlib::XKeysymToKeycode(display, keysym as xlib::KeySym);
+
+        if code != 0 {
+            xtest::XTestFakeKeyEvent(display, code as u32, down as i32, 0);
+            xlib::XFlush(display);
+        }
+
+        xlib::XCloseDisplay(display);
+
+        match code {
+            0 => Err(anyhow!("{:?} isn't on the keyboard map of the X server", key)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn system_toggle(key: Key, _down: bool) -> Result<()> {
+    Err(anyhow!("Pressing {:?} isn't supported on this system", key))
+}
+
+/// reads a shortcut like "ctrl+shift+t", or a sequence of them pressed one after another like "alt+f, s",
+/// into the keys with the modifiers held for each of them - names are the ones of the key picker in any case
+pub fn parse_shortcut(text: &str) -> Result<Vec<(Key, Vec<Flag>)>> {
+    let mut chords = Vec::new();
+    let mut flags = Vec::new();
+    let mut token = String::new();
+
+    for character in text.chars() {
+        match character {
+            // a separator where a key is expected is the key itself, e.g. "ctrl++" or "ctrl+,"
+            '+' | ',' if token.trim().is_empty() => token.push(character),
+            '+' if token.trim().eq_ignore_ascii_case("numpad") => token.push(character),
+            '+' => {
+                flags.push(modifier_from_name(token.trim())?);
+                token.clear();
+            },
+            ',' => {
+                chords.push((key_from_name(token.trim())?, flags));
+                flags = Vec::new();
+                token.clear();
+            },
+            _ => token.push(character),
+        }
+    }
+
+    if !token.trim().is_empty() {
+        chords.push((key_from_name(token.trim())?, flags));
+    } else if !flags.is_empty() {
+        return Err(anyhow!("Missing the key after the modifiers at the end of \"{}\"", text));
+    }
+
+    if chords.is_empty() {
+        return Err(anyhow!("No keys in the shortcut"));
+    }
+
+    Ok(chords)
+}
+
+/// the shortcut the way parse_shortcut reads it, e.g. "ctrl+shift+t"
+pub fn format_shortcut(key: &Key, flags: &[Flag]) -> String {
+    let mut parts: Vec<String> = flags.iter().filter_map(|flag| match flag {
+        Flag::Control => Some("ctrl"),
+        Flag::Alt => Some("alt"),
+        Flag::Shift => Some("shift"),
+        Flag::Meta => Some("meta"),
+        Flag::Help => None,
+    }).map(String::from).collect();
+
+    parts.push(key.to_string().to_lowercase());
+
+    parts.join("+")
+}
+
+fn modifier_from_name(name: &str) -> Result<Flag> {
+    Ok(match name.to_lowercase().as_str() {
+        "ctrl" | "control" => Flag::Control,
+        "alt" => Flag::Alt,
+        "shift" => Flag::Shift,
+        "meta" | "super" | "win" | "cmd" => Flag::Meta,
+        "" => return Err(anyhow!("Missing a modifier before \"+\"")),
+        _ => return Err(anyhow!("\"{}\" isn't a modifier, only the last key of a combination can be another key", name)),
+    })
+}
+
+fn key_from_name(name: &str) -> Result<Key> {
+    let lowercase = name.to_lowercase();
+
+    // common names that differ from the ones in the picker
+    match lowercase.as_str() {
+        "control" => return Ok(Key::Control),
+        "escape" => return Ok(Key::Escape),
+        "return" => return Ok(Key::Return),
+        "delete" => return Ok(Key::Delete),
+        "super" | "win" | "cmd" => return Ok(Key::Meta),
+        _ => {},
+    }
+
+    let option = Key::all_string_options().into_iter().find(|option| option.to_lowercase() == lowercase);
+
+    match option {
+        Some(option) => Key::from_str(&option),
+        None => Key::from_str(name).map_err(|_| anyhow!("Unknown key \"{}\"", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autopilot::key::Flag;
+
+    use super::{Key, parse_shortcut, format_shortcut};
+
+    #[test]
+    fn parse_chord() {
+        assert_eq!(parse_shortcut("ctrl+shift+t").unwrap(), vec![(Key::Character('t'), vec![Flag::Control, Flag::Shift])]);
+        assert_eq!(parse_shortcut(" Alt + Page Up ").unwrap(), vec![(Key::PageUp, vec![Flag::Alt])]);
+    }
+
+    #[test]
+    fn parse_sequence() {
+        assert_eq!(parse_shortcut("alt+f, s").unwrap(), vec![(Key::Character('f'), vec![Flag::Alt]), (Key::Character('s'), vec![])]);
+    }
+
+    #[test]
+    fn parse_separators_as_keys() {
+        assert_eq!(parse_shortcut("ctrl++").unwrap(), vec![(Key::Character('+'), vec![Flag::Control])]);
+        assert_eq!(parse_shortcut("ctrl+,, a").unwrap(), vec![(Key::Character(','), vec![Flag::Control]), (Key::Character('a'), vec![])]);
+        assert_eq!(parse_shortcut("numpad +").unwrap(), vec![(Key::NumpadAdd, vec![])]);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_shortcut("").is_err());
+        assert!(parse_shortcut("ctrl+").is_err());
+        assert!(parse_shortcut("t+ctrl").is_err());
+        assert!(parse_shortcut("ctrl+nothing").is_err());
+    }
+
+    #[test]
+    fn format_parses_back() {
+        for (key, flags) in [(Key::Character('t'), vec![Flag::Control, Flag::Shift]), (Key::NumpadAdd, vec![Flag::Meta]), (Key::Character(','), vec![Flag::Alt]), (Key::PageDown, vec![])] {
+            assert_eq!(parse_shortcut(&format_shortcut(&key, &flags)).unwrap(), vec![(key, flags)]);
+        }
+    }
+}
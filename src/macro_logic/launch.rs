@@ -0,0 +1,237 @@
+use std::process::{Child, Command, ExitStatus};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+
+/// starts a program, optionally waiting until it exits and checking how it went
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Launch {
+    pub program: String,
+    pub arguments: String,              // written like on a command line, quotes keep an argument with spaces together
+    pub working_dir: String,            // empty starts the program wherever ScreenMacro was started
+    pub env: Vec<(String, String)>,     // set on top of the environment ScreenMacro has
+    pub wait: bool,                     // the next step only runs once the program exits
+    pub timeout_seconds: u64,           // how long the step waits, the program keeps running after that
+    pub check_exit_code: bool,          // when waiting, an exit code other than 0 fails the macro
+    #[serde(default)]
+    pub open_with_default: bool         // the program is any file, opened by the application the desktop has for it
+}
+
+impl Default for Launch {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            arguments: String::new(),
+            working_dir: String::new(),
+            env: Vec::new(),
+            wait: false,
+            timeout_seconds: 60,
+            check_exit_code: false,
+            open_with_default: false
+        }
+    }
+}
+
+impl Launch {
+    /// how often a waiting step checks whether the program exited
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// how long opening a file waits for the opener to fail, some of them keep running with the application
+    const OPENER_WAIT: Duration = Duration::from_secs(10);
+
+    /// only for a launch that was saved before it had any options
+    pub fn from_command(command: String) -> Self {
+        Self { program: command, ..Default::default() }
+    }
+
+    pub fn spawn(&self) -> Result<Child> {
+        self.command(&self.program)
+            .args(split_arguments(&self.arguments)?)
+            .spawn()
+            .map_err(|err| anyhow!("Failed to start \"{}\": {}", self.program, err))
+    }
+
+    /// opens the file with its default application, `opener` is a command line the file is added to -
+    /// when it's empty the usual openers of the system are tried in turn until one of them manages
+    pub fn open(&self, opener: &str) -> Result<()> {
+        match opener.trim() {
+            "" => self.open_with_any(&default_openers()),
+            _ => self.open_with_any(&[split_arguments(opener)?]),
+        }
+    }
+
+    fn open_with_any(&self, openers: &[Vec<String>]) -> Result<()> {
+        let mut errors = Vec::new();
+
+        for opener in openers {
+            match self.open_with(opener) {
+                Ok(()) => return Ok(()),
+                Err(err) => errors.push(err.to_string()),
+            }
+        }
+
+        Err(anyhow!("Failed to open \"{}\" with its default application:\n{}", self.program, errors.join("\n")))
+    }
+
+    fn open_with(&self, opener: &[String]) -> Result<()> {
+        let (program, arguments) = opener.split_first().ok_or(anyhow!("The opener command is empty"))?;
+
+        let mut child = self.command(program)
+            .args(arguments)
+            .arg(&self.program)
+            .spawn()
+            .map_err(|err| anyhow!("Failed to start \"{}\": {}", program, err))?;
+
+        // still running means it's showing the file itself or waiting for the application, either is fine
+        match Launch::wait_for_exit(&mut child, Launch::OPENER_WAIT)? {
+            Some(status) if !status.success() => Err(anyhow!("\"{}\" failed with {}", program, status)),
+            _ => Ok(()),
+        }
+    }
+
+    /// the working directory and the variables of the step are the same for the program and the openers
+    fn command(&self, program: &str) -> Command {
+        let mut command = Command::new(program);
+
+        if !self.working_dir.is_empty() {
+            command.current_dir(&self.working_dir);
+        }
+
+        command.envs(self.env.iter().filter(|(name, _)| !name.is_empty()).map(|(name, value)| (name, value)));
+
+        command
+    }
+
+    /// the exit status, None when the program is still running after the timeout
+    pub fn wait_for_exit(child: &mut Child, timeout: Duration) -> Result<Option<ExitStatus>> {
+        let start_time = Instant::now();
+
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(Some(status));
+            }
+
+            if start_time.elapsed() > timeout {
+                return Ok(None);
+            }
+
+            sleep(Launch::POLL_INTERVAL);
+        }
+    }
+
+    /// an error for anything but a successful exit when the step checks the exit code
+    pub fn check_status(&self, status: ExitStatus) -> Result<()> {
+        if !self.check_exit_code || status.success() {
+            return Ok(());
+        }
+
+        Err(match status.code() {
+            Some(code) => anyhow!("\"{}\" exited with code {}", self.program, code),
+            None => anyhow!("\"{}\" was ended without an exit code", self.program),
+        })
+    }
+}
+
+/// commands that open a file with its default application, the file is added as the last argument
+#[cfg(target_os = "windows")]
+fn default_openers() -> Vec<Vec<String>> {
+    // the empty argument is the title of the window start would open, otherwise a quoted file would be taken for it
+    vec![vec!["cmd".into(), "/C".into(), "start".into(), "".into()]]
+}
+
+#[cfg(target_os = "macos")]
+fn default_openers() -> Vec<Vec<String>> {
+    vec![vec!["open".into()]]
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn default_openers() -> Vec<Vec<String>> {
+    vec![vec!["xdg-open".into()], vec!["gio".into(), "open".into()]]
+}
+
+/// splits the arguments at spaces except inside double or single quotes, the quotes themselves are left out -
+/// backslashes are kept as they are so Windows paths don't need escaping
+pub fn split_arguments(arguments: &str) -> Result<Vec<String>> {
+    let mut res = Vec::new();
+    let mut current: Option<String> = None;     // None between arguments, an empty quoted argument is still one
+    let mut quote: Option<char> = None;
+
+    for character in arguments.chars() {
+        match (quote, character) {
+            (Some(open), _) if character == open => quote = None,
+            (Some(_), _) => current.get_or_insert_with(String::new).push(character),
+            (None, '"' | '\'') => {
+                quote = Some(character);
+                current.get_or_insert_with(String::new);
+            },
+            (None, _) if character.is_whitespace() => res.extend(current.take()),
+            (None, _) => current.get_or_insert_with(String::new).push(character),
+        }
+    }
+
+    if let Some(open) = quote {
+        return Err(anyhow!("The arguments have a {} that's never closed", open));
+    }
+
+    res.extend(current);
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Launch, split_arguments};
+
+    #[test]
+    fn split_at_spaces() {
+        assert_eq!(split_arguments("  file.txt   -n 2 ").unwrap(), vec!["file.txt", "-n", "2"]);
+        assert!(split_arguments("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn split_keeps_quoted_spaces() {
+        assert_eq!(split_arguments(r#""my file.txt" --name='a "b"' C:\dir\"#).unwrap(), vec!["my file.txt", "--name=a \"b\"", "C:\\dir\\"]);
+        assert_eq!(split_arguments("'' x").unwrap(), vec!["", "x"]);
+    }
+
+    #[test]
+    fn split_fails_on_open_quote() {
+        assert!(split_arguments("\"file.txt").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exit_code_is_checked() {
+        let launch = Launch { program: "sh".into(), arguments: "-c 'exit 3'".into(), wait: true, check_exit_code: true, ..Default::default() };
+
+        let mut child = launch.spawn().unwrap();
+        let status = Launch::wait_for_exit(&mut child, Duration::from_secs(10)).unwrap().unwrap();
+
+        assert_eq!(status.code(), Some(3));
+        assert!(launch.check_status(status).is_err());
+        assert!(Launch { check_exit_code: false, ..launch }.check_status(status).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn opener_gets_the_file() {
+        let launch = Launch { program: "song.mp3".into(), open_with_default: true, ..Default::default() };
+
+        assert!(launch.open(r#"sh -c 'test "$0" = song.mp3'"#).is_ok());
+        assert!(launch.open(r#"sh -c 'test "$0" = other.mp3'"#).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn openers_fall_back() {
+        let launch = Launch { program: "song.mp3".into(), open_with_default: true, ..Default::default() };
+
+        assert!(launch.open_with_any(&[vec!["screenmacro-missing-opener".into()], vec!["false".into()], vec!["true".into()]]).is_ok());
+        assert!(launch.open_with_any(&[vec!["screenmacro-missing-opener".into()], vec!["false".into()]]).is_err());
+        assert!(launch.open_with_any(&[vec![]]).is_err());
+    }
+}
@@ -1,19 +1,28 @@
 use anyhow::{Result, anyhow};
 use autopilot::key::Flag;
-use iced::widget::svg::{Svg, Handle};
-use image::{RgbImage, DynamicImage};
-use autopilot::{bitmap, mouse, geometry::Point, key};
+use image::RgbaImage;
+use autopilot::{mouse, key};
 use async_std::task::sleep as async_sleep;
 use serde::{Serialize, Deserialize};
 
 use std::fs::File;
 use std::path::PathBuf;
-use std::process::Command;
 use std::sync::{Arc, Mutex};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
 use super::Settings;
+use super::screen;
+use super::color_check::ColorCheck;
+use super::drag::{Drag, DragEnd};
+use super::launch::Launch;
+use super::shell::Shell;
+use super::variables::Variables;
+use super::variable_check::{VariableCheck, CheckFailure};
+use super::clipboard::ClipboardContent;
+use super::keys::{self, Key};
+use super::image_target::{self, ImageTarget};
+use super::image_search::{self, MatchMode, Template};
 use super::macro_serde::MacroSerializable;
 
 #[derive(Clone, Debug)]
@@ -35,14 +44,28 @@ impl Macro {
     }
 
     pub async fn execute_macro(macro_data: Macro, continue_signal: Arc<Mutex<bool>>, is_running: Arc<Mutex<bool>>) -> Result<()> {
-        for step in macro_data.macro_steps.iter() {
-            match step.dispatch(&macro_data.settings) {
-                Ok(stop_early) => if stop_early { break },
-                Err(err) => {
-                    *(is_running.lock().unwrap()) = false;
-                    return Err(err);
-                },
+        let mut held_keys = HeldKeys::default();
+        let mut variables = Variables::new();
+        let res = Macro::execute_steps(&macro_data, &continue_signal, &mut held_keys, &mut variables).await;
+
+        // keys a KeyDown step pressed would otherwise stay down for the user after the macro finishes, is stopped or fails
+        held_keys.release_all();
+        *(is_running.lock().unwrap()) = false;
+
+        res
+    }
+
+    async fn execute_steps(macro_data: &Macro, continue_signal: &Arc<Mutex<bool>>, held_keys: &mut HeldKeys, variables: &mut Variables) -> Result<()> {
+        let mut step_index = 0;
+
+        while let Some(step) = macro_data.macro_steps.get(step_index) {
+            match step.dispatch(&macro_data.settings, variables)? {
+                StepOutcome::Continue => step_index += 1,
+                StepOutcome::SkipNext(count) => step_index += 1 + count as usize,
+                StepOutcome::Stop => break,
             };
+
+            held_keys.track(step);
             
             async_sleep(Duration::from_secs(1)).await;
             
@@ -50,8 +73,6 @@ impl Macro {
                 break;
             }
         }
-
-        *(is_running.lock().unwrap()) = false;
         
         Ok(())
     }
@@ -84,45 +105,225 @@ pub enum ClickPoint {
     CenterRight,
     BottomLeft,
     BottomMiddle,
-    BottomRight
+    BottomRight,
+    Offset(f64, f64, OffsetUnit)    // x, y from the top left corner of the match, can point outside of the image
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OffsetUnit {
+    #[default]
+    Pixels,
+    Fraction        // of the image size, 0.5 is the middle
+}
+
+/// how a click step clicks once the mouse is in place
+#[derive(Clone, Debug)]
+pub struct ClickOptions {
+    pub button: mouse::Button,
+    pub count: u32,             // 2 for a double click, 3 for a triple click
+    pub modifiers: Vec<Flag>    // held down for all of the clicks, e.g. ctrl+click to select several items
+}
+
+impl Default for ClickOptions {
+    fn default() -> Self {
+        Self { button: mouse::Button::Left, count: 1, modifiers: Vec::new() }
+    }
+}
+
+/// keys pressed by KeyDown steps that no KeyUp step released yet
+#[derive(Default)]
+struct HeldKeys(Vec<Key>);
+
+impl HeldKeys {
+    fn track(&mut self, step: &MacroStep) {
+        match step {
+            MacroStep::KeyDown(key) if !self.0.contains(key) => self.0.push(*key),
+            MacroStep::KeyUp(key) => self.0.retain(|held| held != key),
+            _ => ()
+        }
+    }
+
+    fn release_all(&mut self) {
+        for key in self.0.drain(..).rev() {
+            // nothing else can be done about a key that fails to be released this late
+            key.toggle(false, &[]).ok();
+        }
+    }
+}
+
+/// which image of a step is captured or edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSlot {
+    Template(usize),    // one of the alternatives, steps with a single image only have 0, drags have their source at 0 and destination at 1
+    Anchor              // the image the search of the step is limited around
+}
+
+/// what the macro does after a step is done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    Continue,
+    Stop,               // the macro ends here, e.g. a step timed out and the settings say to stop the whole macro
+    SkipNext(u32)       // a check failed, this many of the following steps are skipped
 }
 
 #[derive(Debug, Clone)]
 pub enum MacroStep {
-    Launch(String),                         // has the command
-    ClickImage(Option<RgbImage>, ClickPoint, f32),    // image name, click point, allowed difference
-    MoveToImage(Option<RgbImage>, ClickPoint, f32),   // image name, click point which is a move point here, allowed difference
+    Launch(Launch),
+    ClickImage(ImageTarget, ClickOptions),  // what is searched for, button and count
+    MoveToImage(ImageTarget),               // the click point of the target is a move point here
     TypeText(String, Vec<Flag>),
-    PressKey(key::KeyCode, Vec<Flag>),
+    PressKey(Key, Vec<Flag>),
     Scroll(mouse::ScrollDirection, u32),
-    WaitTime(u64)
+    WaitTime(u64),
+    WaitImageGone(Option<RgbaImage>, f32, MatchMode, u64),    // image name, allowed difference, what is compared, how long it has to stay gone in ms
+    WaitColor(ColorCheck),
+    CheckColor(ColorCheck, u32),            // the check, how many of the next steps are skipped when the color doesn't match
+    MoveTo(i32, i32),                       // desktop coordinates, from the top left corner of the target window if the macro has one
+    MoveBy(i32, i32),                       // from wherever the mouse is
+    ClickAt(i32, i32, ClickOptions),        // same coordinates as MoveTo
+    Drag(Drag),
+    KeyDown(Key),                           // stays pressed until a KeyUp step or the end of the macro
+    KeyUp(Key),
+    HoldKey(Key, u64),                      // key, how long it's held in ms
+    RunShell(Shell),
+    SetClipboardText(String),               // variables in it are replaced like in TypeText
+    SetClipboardImage(Option<RgbaImage>),
+    ReadClipboard(String),                  // name of the variable that gets the text
+    WaitClipboardChange,                    // until the clipboard holds something else than the last clipboard step saw
+    CheckVariable(VariableCheck)
 }
 
 impl MacroStep {
-    pub fn dispatch(&self, settings: &Settings) -> Result<bool> {
+    /// `variables` live as long as the macro runs, steps store values in them for the steps after them
+    pub fn dispatch(&self, settings: &Settings, variables: &mut Variables) -> Result<StepOutcome> {
         match self {
-            MacroStep::Launch(command) => MacroStep::execute_launch(command)?,
-            MacroStep::ClickImage(img_data, point, allowed_diff) => return MacroStep::execute_click_image(img_data.as_ref().ok_or(anyhow!("Missing image data"))?, point, allowed_diff, settings),
-            MacroStep::MoveToImage(img_data, move_point, allowed_diff) => return MacroStep::execute_move_to_image(img_data.as_ref().ok_or(anyhow!("Missing image data"))?, move_point, allowed_diff, settings),
-            MacroStep::TypeText(text, flags) => MacroStep::execute_type_text(text, flags)?,
+            MacroStep::Launch(launch) => return MacroStep::execute_launch(launch, settings),
+            MacroStep::ClickImage(target, options) => return MacroStep::execute_click_image(target, options, settings),
+            MacroStep::MoveToImage(target) => return MacroStep::execute_move_to_image(target, settings),
+            MacroStep::TypeText(text, flags) => MacroStep::execute_type_text(&variables.expand(text), flags)?,
             MacroStep::PressKey(key, flags) => MacroStep::execute_press_key(key, flags)?,
             MacroStep::Scroll(direction, amount) => MacroStep::execute_scroll(direction, amount)?,
             MacroStep::WaitTime(milliseconds) => MacroStep::execute_wait(*milliseconds)?,
+            MacroStep::WaitImageGone(img_data, allowed_diff, mode, settle_time) => return MacroStep::execute_wait_image_gone(img_data.as_ref().ok_or(anyhow!("Missing image data"))?, allowed_diff, mode, *settle_time, settings),
+            MacroStep::WaitColor(check) => return MacroStep::execute_wait_color(check, settings),
+            MacroStep::CheckColor(check, skip_count) => return MacroStep::execute_check_color(check, *skip_count, settings),
+            MacroStep::MoveTo(x, y) => MacroStep::execute_move_to(*x, *y, settings)?,
+            MacroStep::MoveBy(x, y) => MacroStep::execute_move_by(*x, *y)?,
+            MacroStep::ClickAt(x, y, options) => MacroStep::execute_click_at(*x, *y, options, settings)?,
+            MacroStep::Drag(drag) => return MacroStep::execute_drag(drag, settings),
+            MacroStep::KeyDown(key) => MacroStep::execute_toggle_key(key, true)?,
+            MacroStep::KeyUp(key) => MacroStep::execute_toggle_key(key, false)?,
+            MacroStep::HoldKey(key, time) => MacroStep::execute_hold_key(key, *time)?,
+            MacroStep::RunShell(shell) => return MacroStep::execute_run_shell(shell, variables, settings),
+            MacroStep::SetClipboardText(text) => MacroStep::execute_set_clipboard(ClipboardContent::Text(variables.expand(text)), variables)?,
+            MacroStep::SetClipboardImage(image) => MacroStep::execute_set_clipboard(ClipboardContent::Image(image.clone().ok_or(anyhow!("Missing image data"))?), variables)?,
+            MacroStep::ReadClipboard(name) => MacroStep::execute_read_clipboard(name, variables)?,
+            MacroStep::WaitClipboardChange => return MacroStep::execute_wait_clipboard_change(variables, settings),
+            MacroStep::CheckVariable(check) => return MacroStep::execute_check_variable(check, variables),
+        }
+
+        Ok(StepOutcome::Continue)
+    }
+
+    /// runs the step outside of a macro the same way a macro runs it, keys it presses down are released afterwards
+    /// and it has no earlier steps that could have set variables
+    pub fn run_alone(&self, settings: &Settings) -> Result<StepOutcome> {
+        let mut held_keys = HeldKeys::default();
+        let res = self.dispatch(settings, &mut Variables::new());
+
+        if res.is_ok() {
+            held_keys.track(self);
+        }
+
+        held_keys.release_all();
+
+        res
+    }
+
+    /// the image the step searches for, for color checks it's the image the position is relative to
+    pub fn image(&self, slot: ImageSlot) -> Option<&RgbaImage> {
+        match (self, slot) {
+            (MacroStep::ClickImage(target, _) | MacroStep::MoveToImage(target), _) => target.image(slot),
+            (MacroStep::WaitImageGone(image, ..), ImageSlot::Template(0)) => image.as_ref(),
+            (MacroStep::WaitColor(check) | MacroStep::CheckColor(check, _), ImageSlot::Template(0)) => check.image.as_ref(),
+            (MacroStep::Drag(drag), ImageSlot::Template(0)) => drag.source.image.as_ref(),
+            (MacroStep::Drag(drag), ImageSlot::Template(1)) => drag.destination.image.as_ref(),
+            (MacroStep::SetClipboardImage(image), ImageSlot::Template(0)) => image.as_ref(),
+            _ => None
+        }
+    }
+
+    /// what an image step searches for, None for other steps
+    pub fn search_target(&self) -> Option<ImageTarget> {
+        match self {
+            MacroStep::ClickImage(target, _) | MacroStep::MoveToImage(target) => Some(target.clone()),
+            MacroStep::WaitImageGone(image, allowed_diff, mode, _) => Some(ImageTarget { images: image.iter().cloned().collect(), allowed_diff: *allowed_diff, mode: *mode, ..Default::default() }),
+            _ => None
+        }
+    }
+
+    /// for image steps, the others ignore it
+    pub fn set_allowed_diff(&mut self, new_allowed_diff: f32) {
+        match self {
+            MacroStep::ClickImage(ImageTarget { allowed_diff, .. }, _) | MacroStep::MoveToImage(ImageTarget { allowed_diff, .. }) | MacroStep::WaitImageGone(_, allowed_diff, ..) => *allowed_diff = new_allowed_diff,
+            _ => ()
+        }
+    }
+
+    /// replaces the image from `image` with one made outside of the step (captured, edited), steps without an image ignore it
+    /// a slot past the last alternative adds a new one, an anchor slot gives the step an anchor if it has none yet
+    pub fn set_image(&mut self, slot: ImageSlot, new_image: RgbaImage) {
+        match (self, slot) {
+            (MacroStep::ClickImage(target, _) | MacroStep::MoveToImage(target), ImageSlot::Anchor) => {
+                target.anchor.get_or_insert_with(Default::default).image = Some(new_image);
+            },
+            (MacroStep::ClickImage(target, _) | MacroStep::MoveToImage(target), ImageSlot::Template(index)) => {
+                match target.images.get_mut(index) {
+                    Some(image) => *image = new_image,
+                    None => target.images.push(new_image),
+                }
+            },
+            (MacroStep::WaitImageGone(image, ..), ImageSlot::Template(_)) => *image = Some(new_image),
+            (MacroStep::WaitColor(check) | MacroStep::CheckColor(check, _), ImageSlot::Template(_)) => {
+                check.image = Some(new_image);
+                check.relative_to_image = true;
+            },
+            (MacroStep::Drag(drag), ImageSlot::Template(index)) => {
+                let end = if index == 0 { &mut drag.source } else { &mut drag.destination };
+
+                end.image = Some(new_image);
+                end.on_image = true;
+            },
+            (MacroStep::SetClipboardImage(image), ImageSlot::Template(_)) => *image = Some(new_image),
+            _ => ()
+        }
+    }
+
+    /// sets the point of steps that go to a fixed position, the others are left alone
+    pub fn set_position(&mut self, new_x: i32, new_y: i32) {
+        match self {
+            MacroStep::MoveTo(x, y) | MacroStep::ClickAt(x, y, _) => (*x, *y) = (new_x, new_y),
+            _ => ()
         }
+    }
 
-        Ok(false)
+    /// sets the key and the modifiers of key press steps, the others are left alone
+    pub fn set_shortcut(&mut self, new_key: Key, new_flags: Vec<Flag>) {
+        if let MacroStep::PressKey(key, flags) = self {
+            (*key, *flags) = (new_key, new_flags);
+        }
     }
 
     pub fn default_launch() -> MacroStep {
-        MacroStep::Launch("".into())
+        MacroStep::Launch(Default::default())
     }
 
     pub fn default_click_image() -> MacroStep {
-        MacroStep::ClickImage(None, Default::default(), 0.0)
+        MacroStep::ClickImage(Default::default(), Default::default())
     }
 
     pub fn default_await_image() -> MacroStep {
-        MacroStep::MoveToImage(None, Default::default(), 0.0)
+        MacroStep::MoveToImage(Default::default())
     }
 
     pub fn default_type_text() -> MacroStep {
@@ -130,7 +331,7 @@ impl MacroStep {
     }
 
     pub fn default_press_key() -> MacroStep {
-        MacroStep::PressKey(key::KeyCode::LeftArrow, vec![])
+        MacroStep::PressKey(Key::LeftArrow, vec![])
     }
 
     pub fn default_scroll() -> MacroStep {
@@ -141,47 +342,313 @@ impl MacroStep {
         MacroStep::WaitTime(0)
     }
 
-    fn execute_launch(command: &str) -> Result<()> {
-        Command::new(command).spawn()?;
+    pub fn default_wait_image_gone() -> MacroStep {
+        MacroStep::WaitImageGone(None, 0.0, Default::default(), 500)
+    }
 
-        Ok(())
+    pub fn default_wait_color() -> MacroStep {
+        MacroStep::WaitColor(Default::default())
+    }
+
+    pub fn default_check_color() -> MacroStep {
+        MacroStep::CheckColor(Default::default(), 1)
+    }
+
+    pub fn default_move_to() -> MacroStep {
+        MacroStep::MoveTo(0, 0)
+    }
+
+    pub fn default_move_by() -> MacroStep {
+        MacroStep::MoveBy(0, 0)
+    }
+
+    pub fn default_click_at() -> MacroStep {
+        MacroStep::ClickAt(0, 0, Default::default())
+    }
+
+    pub fn default_drag() -> MacroStep {
+        MacroStep::Drag(Default::default())
+    }
+
+    pub fn default_key_down() -> MacroStep {
+        MacroStep::KeyDown(Key::Shift)
     }
 
-    fn execute_click_image(img_data: &RgbImage, point: &ClickPoint, allowed_diff: &f32, settings: &Settings) -> Result<bool> {
-        let move_res = MacroStep::execute_move_to_image(img_data, point, allowed_diff, settings)?;
+    pub fn default_key_up() -> MacroStep {
+        MacroStep::KeyUp(Key::Shift)
+    }
+
+    pub fn default_hold_key() -> MacroStep {
+        MacroStep::HoldKey(Key::Space, 1000)
+    }
+
+    pub fn default_run_shell() -> MacroStep {
+        MacroStep::RunShell(Default::default())
+    }
+
+    pub fn default_set_clipboard_text() -> MacroStep {
+        MacroStep::SetClipboardText("".into())
+    }
+
+    pub fn default_set_clipboard_image() -> MacroStep {
+        MacroStep::SetClipboardImage(None)
+    }
+
+    pub fn default_read_clipboard() -> MacroStep {
+        MacroStep::ReadClipboard("clipboard".into())
+    }
+
+    pub fn default_wait_clipboard_change() -> MacroStep {
+        MacroStep::WaitClipboardChange
+    }
+
+    pub fn default_check_variable() -> MacroStep {
+        MacroStep::CheckVariable(Default::default())
+    }
 
-        if move_res {
-            return Ok(true);
+    fn execute_launch(launch: &Launch, settings: &Settings) -> Result<StepOutcome> {
+        if launch.open_with_default {
+            launch.open(&settings.opener)?;
+            return Ok(StepOutcome::Continue);
         }
 
-        mouse::click(mouse::Button::Left, None);
+        let mut child = launch.spawn()?;
 
-        Ok(false)
+        if !launch.wait {
+            return Ok(StepOutcome::Continue);
+        }
+
+        match Launch::wait_for_exit(&mut child, Duration::from_secs(launch.timeout_seconds))? {
+            Some(status) => launch.check_status(status)?,
+            None => return Ok(MacroStep::timeout_outcome(settings)),
+        }
+
+        Ok(StepOutcome::Continue)
     }
 
-    fn execute_move_to_image(img_data: &RgbImage, move_point: &ClickPoint, allowed_diff: &f32, settings: &Settings) -> Result<bool> {
+    fn execute_click_image(target: &ImageTarget, options: &ClickOptions, settings: &Settings) -> Result<StepOutcome> {
+        let move_res = MacroStep::execute_move_to_image(target, settings)?;
+
+        if move_res != StepOutcome::Continue {
+            return Ok(move_res);
+        }
+
+        options.click();
+
+        Ok(StepOutcome::Continue)
+    }
+
+    fn execute_move_to_image(target: &ImageTarget, settings: &Settings) -> Result<StepOutcome> {
         let start_time = Instant::now();
-        let target_img_bitmap = bitmap::Bitmap::new(DynamicImage::ImageRgb8(img_data.clone()), None);
+        let templates = target.prepare()?;
 
         loop {
-            let screen = bitmap::capture_screen()?;
-            
-            if let Some(found_point) = screen.find_bitmap(&target_img_bitmap, Some(*allowed_diff as f64), None, None) {
-                let (mult_x, mult_y) = move_point.to_mults();
-                mouse::move_to(Point::new(found_point.x + mult_x * (img_data.width() as f64), found_point.y + mult_y * (img_data.height() as f64)))?;
+            let search_start = Instant::now();
+            let screen = screen::capture_for(settings)?;
+
+            let found = target.locate(&screen.image, &templates).and_then(|(position, slot)| Some((position, target.image(slot)?)));
+
+            if let Some(((found_x, found_y), image)) = found {
+                let (offset_x, offset_y) = target.point.to_offset(image.width(), image.height());
+                let (x, y) = screen.to_desktop(found_x as f64 + offset_x, found_y as f64 + offset_y);
+                screen::move_mouse(x, y)?;
                 break;
             }
 
-            sleep(Duration::from_millis(300));
+            MacroStep::pause_between_searches(search_start, settings);
 
             println!("{}", start_time.elapsed().as_secs());
 
             if start_time.elapsed().as_secs() > settings.step_timeout_seconds {
-                return Ok(settings.break_whole_macro)
+                return Ok(MacroStep::timeout_outcome(settings))
+            }
+        }
+
+        Ok(StepOutcome::Continue)
+    }
+
+    fn execute_wait_image_gone(img_data: &RgbaImage, allowed_diff: &f32, mode: &MatchMode, settle_time: u64, settings: &Settings) -> Result<StepOutcome> {
+        let start_time = Instant::now();
+        let template = image_target::prepare_template(img_data, mode)?;
+        let mut gone_since: Option<Instant> = None;
+
+        loop {
+            let search_start = Instant::now();
+            let screen = screen::capture_for(settings)?;
+
+            if image_search::find_image(&screen.image, &template, *allowed_diff).is_some() {
+                gone_since = None;      // it came back, e.g. a spinner between two loading stages
+            } else if gone_since.get_or_insert(search_start).elapsed() >= Duration::from_millis(settle_time) {
+                break;
+            }
+
+            MacroStep::pause_between_searches(search_start, settings);
+
+            if start_time.elapsed().as_secs() > settings.step_timeout_seconds {
+                return Ok(MacroStep::timeout_outcome(settings))
             }
         }
 
-        Ok(false)
+        Ok(StepOutcome::Continue)
+    }
+
+    fn execute_wait_color(check: &ColorCheck, settings: &Settings) -> Result<StepOutcome> {
+        let start_time = Instant::now();
+        let anchor = check.prepare_anchor()?;
+
+        loop {
+            let search_start = Instant::now();
+
+            if check.matches_screen(anchor.as_ref(), settings)? {
+                break;
+            }
+
+            MacroStep::pause_between_searches(search_start, settings);
+
+            if start_time.elapsed().as_secs() > settings.step_timeout_seconds {
+                return Ok(MacroStep::timeout_outcome(settings))
+            }
+        }
+
+        Ok(StepOutcome::Continue)
+    }
+
+    fn execute_check_color(check: &ColorCheck, skip_count: u32, settings: &Settings) -> Result<StepOutcome> {
+        let anchor = check.prepare_anchor()?;
+
+        if check.matches_screen(anchor.as_ref(), settings)? {
+            Ok(StepOutcome::Continue)
+        } else {
+            Ok(StepOutcome::SkipNext(skip_count))
+        }
+    }
+
+    fn execute_drag(drag: &Drag, settings: &Settings) -> Result<StepOutcome> {
+        let start_time = Instant::now();
+        let source_template = drag.source.prepare()?;
+        let destination_template = drag.destination.prepare()?;
+
+        let (source_x, source_y) = match MacroStep::wait_for_drag_end(&drag.source, source_template.as_ref(), start_time, settings)? {
+            Some(source) => source,
+            None => return Ok(MacroStep::timeout_outcome(settings)),
+        };
+
+        screen::move_mouse(source_x, source_y)?;
+        mouse::toggle(drag.button, true);
+
+        let outcome = MacroStep::drag_to_destination(drag, (source_x, source_y), destination_template.as_ref(), start_time, settings);
+
+        // released whatever happened on the way, a button left pressed would drag everything the user does afterwards
+        mouse::toggle(drag.button, false);
+
+        outcome
+    }
+
+    /// the destination is searched for only after pressing, drop targets often appear once something is dragged
+    fn drag_to_destination(drag: &Drag, source: (f64, f64), template: Option<&Template>, start_time: Instant, settings: &Settings) -> Result<StepOutcome> {
+        sleep(Duration::from_millis(drag.press_hold_ms));
+
+        let destination = match MacroStep::wait_for_drag_end(&drag.destination, template, start_time, settings)? {
+            Some(destination) => destination,
+            None => return Ok(MacroStep::timeout_outcome(settings)),
+        };
+
+        for (x, y) in Drag::path(source, destination, drag.move_ms) {
+            screen::move_mouse(x, y)?;
+            sleep(Drag::STEP);
+        }
+
+        sleep(Duration::from_millis(drag.release_hold_ms));
+
+        Ok(StepOutcome::Continue)
+    }
+
+    /// None once the step timed out, both ends share the timeout of the step
+    fn wait_for_drag_end(end: &DragEnd, template: Option<&Template>, start_time: Instant, settings: &Settings) -> Result<Option<(f64, f64)>> {
+        loop {
+            let search_start = Instant::now();
+
+            if let Some(position) = end.locate(template, settings)? {
+                return Ok(Some(position));
+            }
+
+            MacroStep::pause_between_searches(search_start, settings);
+
+            if start_time.elapsed().as_secs() > settings.step_timeout_seconds {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn timeout_outcome(settings: &Settings) -> StepOutcome {
+        if settings.break_whole_macro {
+            StepOutcome::Stop
+        } else {
+            StepOutcome::Continue
+        }
+    }
+
+    fn pause_between_searches(search_start: Instant, settings: &Settings) {
+        // waiting between searches at least as long as the search took keeps big screens from hogging the CPU
+        sleep(Duration::from_millis(settings.search_interval_ms).max(search_start.elapsed()));
+    }
+
+    fn execute_run_shell(shell: &Shell, variables: &mut Variables, settings: &Settings) -> Result<StepOutcome> {
+        match shell.run(variables)? {
+            true => Ok(StepOutcome::Continue),
+            false => Ok(MacroStep::timeout_outcome(settings)),
+        }
+    }
+
+    fn execute_set_clipboard(content: ClipboardContent, variables: &mut Variables) -> Result<()> {
+        content.write()?;
+        variables.clipboard = Some(content);
+
+        Ok(())
+    }
+
+    fn execute_read_clipboard(name: &str, variables: &mut Variables) -> Result<()> {
+        match ClipboardContent::read() {
+            Some(ClipboardContent::Text(text)) => {
+                variables.set(name, text.clone());
+                variables.clipboard = Some(ClipboardContent::Text(text));
+
+                Ok(())
+            },
+            _ => Err(anyhow!("The clipboard doesn't hold any text")),
+        }
+    }
+
+    fn execute_wait_clipboard_change(variables: &mut Variables, settings: &Settings) -> Result<StepOutcome> {
+        let start_time = Instant::now();
+
+        loop {
+            let search_start = Instant::now();
+            let content = ClipboardContent::read();
+
+            if content != variables.clipboard {
+                variables.clipboard = content;
+                return Ok(StepOutcome::Continue);
+            }
+
+            MacroStep::pause_between_searches(search_start, settings);
+
+            if start_time.elapsed().as_secs() > settings.step_timeout_seconds {
+                return Ok(MacroStep::timeout_outcome(settings));
+            }
+        }
+    }
+
+    fn execute_check_variable(check: &VariableCheck, variables: &Variables) -> Result<StepOutcome> {
+        if check.passes(variables)? {
+            return Ok(StepOutcome::Continue);
+        }
+
+        match check.on_fail {
+            CheckFailure::SkipNext(count) => Ok(StepOutcome::SkipNext(count)),
+            CheckFailure::Stop => Ok(StepOutcome::Stop),
+            CheckFailure::Fail => Err(anyhow!(check.failure_message(variables))),
+        }
     }
 
     fn execute_type_text(text: &str, flags: &Vec<Flag>) -> Result<()> {
@@ -190,10 +657,18 @@ impl MacroStep {
         Ok(())
     }
 
-    fn execute_press_key(key: &key::KeyCode, flags: &Vec<Flag>) -> Result<()> {
-        key::tap(&key::Code(*key), &flags[..], 0, 0);
+    fn execute_press_key(key: &Key, flags: &[Flag]) -> Result<()> {
+        key.tap(flags)
+    }
 
-        Ok(())
+    fn execute_toggle_key(key: &Key, down: bool) -> Result<()> {
+        key.toggle(down, &[])
+    }
+
+    fn execute_hold_key(key: &Key, time: u64) -> Result<()> {
+        key.toggle(true, &[])?;
+        sleep(Duration::from_millis(time));
+        key.toggle(false, &[])
     }
 
     fn execute_scroll(direction: &mouse::ScrollDirection, clicks: &u32) -> Result<()> {
@@ -202,6 +677,26 @@ impl MacroStep {
         Ok(())
     }
 
+    fn execute_move_to(x: i32, y: i32, settings: &Settings) -> Result<()> {
+        let (origin_x, origin_y) = screen::origin(settings)?;
+
+        screen::move_mouse(origin_x + x as f64, origin_y + y as f64)
+    }
+
+    fn execute_move_by(x: i32, y: i32) -> Result<()> {
+        let (cursor_x, cursor_y) = screen::cursor_position();
+
+        screen::move_mouse(cursor_x + x as f64, cursor_y + y as f64)
+    }
+
+    fn execute_click_at(x: i32, y: i32, options: &ClickOptions, settings: &Settings) -> Result<()> {
+        MacroStep::execute_move_to(x, y, settings)?;
+
+        options.click();
+
+        Ok(())
+    }
+
     fn execute_wait(time: u64) -> Result<()> {
         sleep(Duration::from_millis(time));
 
@@ -216,50 +711,6 @@ impl Default for MacroStep {
 }
 
 impl ClickPoint {
-    pub fn svg(&self) -> Svg {
-        let (x, y) = match self {
-            ClickPoint::TopLeft => {
-                (3.5, 3.5)
-            },
-            ClickPoint::TopMiddle => {
-                (20.0, 3.5)
-            },
-            ClickPoint::TopRight => {
-                (36.5, 3.5)
-            },
-            ClickPoint::CenterLeft => {
-                (3.5, 15.0)
-            },
-            ClickPoint::CenterMiddle => {
-                (20.0, 15.0)
-            },
-            ClickPoint::CenterRight => {
-                (36.5, 15.0)
-            },
-            ClickPoint::BottomLeft => {
-                (3.5, 26.5)
-            },
-            ClickPoint::BottomMiddle => {
-                (20.0, 26.5)
-            },
-            ClickPoint::BottomRight => {
-                (36.5, 26.5)
-            },
-        };
-
-        let svg_body = format!(
-            r#"<svg viewBox="0 0 40 30" xmlns="http://www.w3.org/2000/svg">
-                    <rect y="28" width="39" height="1.5" style="stroke: rgb(0, 0, 0);" x="0.5"></rect>
-                    <rect y="0.5" width="39" height="1.5" style="stroke: rgb(0, 0, 0);" x="0.5"></rect>
-                    <rect x="0.5" y="0.5" width="1.5" height="29" style="stroke: rgb(0, 0, 0);"></rect>
-                    <rect x="38" y="0.5" width="1.5" height="29" style="stroke: rgb(0, 0, 0);"></rect>
-                    <ellipse style="stroke: rgb(0, 0, 0);" cx="{}" cy="{}" rx="3" ry="3"></ellipse>
-                </svg>"#,
-            x, y);
-
-        Svg::new(Handle::from_memory(svg_body))
-    }
-
     pub fn next(&self) -> Self {
         match &self {
             ClickPoint::TopLeft => Self::TopMiddle,
@@ -271,11 +722,15 @@ impl ClickPoint {
             ClickPoint::BottomLeft => Self::CenterLeft,
             ClickPoint::BottomMiddle => Self::BottomLeft,
             ClickPoint::BottomRight => Self::BottomMiddle,
+            ClickPoint::Offset(..) => Self::CenterMiddle,
         }
     }
 
-    pub fn to_mults(&self) -> (f64, f64) {
-        match self {
+    /// pixels from the top left corner of a match with the given size
+    pub fn to_offset(&self, width: u32, height: u32) -> (f64, f64) {
+        let (mult_x, mult_y) = match self {
+            ClickPoint::Offset(x, y, OffsetUnit::Pixels) => return (*x, *y),
+            ClickPoint::Offset(x, y, OffsetUnit::Fraction) => (*x, *y),
             ClickPoint::TopLeft => (0.0, 0.0),
             ClickPoint::TopMiddle => (0.5, 0.0),
             ClickPoint::TopRight => (1.0, 0.0),
@@ -285,6 +740,34 @@ impl ClickPoint {
             ClickPoint::BottomLeft => (0.0, 1.0),
             ClickPoint::BottomMiddle => (0.5, 1.0),
             ClickPoint::BottomRight => (1.0, 1.0),
+        };
+
+        (mult_x * width as f64, mult_y * height as f64)
+    }
+}
+
+impl ClickOptions {
+    /// pause between the clicks of a double or triple click, well below the double click time of any system
+    const CLICK_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// clicks where the mouse is
+    pub fn click(&self) {
+        let modifiers: Vec<key::KeyCode> = self.modifiers.iter().filter_map(keys::modifier_code).collect();
+
+        for modifier in &modifiers {
+            key::toggle(&key::Code(*modifier), true, &[], 0);
+        }
+
+        for index in 0..self.count.max(1) {
+            if index > 0 {
+                sleep(ClickOptions::CLICK_INTERVAL);
+            }
+
+            mouse::click(self.button, None);
+        }
+
+        for modifier in modifiers.iter().rev() {
+            key::toggle(&key::Code(*modifier), false, &[], 0);
         }
     }
-}
\ No newline at end of file
+}
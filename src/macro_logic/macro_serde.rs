@@ -2,13 +2,13 @@ use std::io::Cursor;
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use autopilot::key::{KeyCode, Flag};
-use autopilot::mouse::ScrollDirection;
+use autopilot::key::Flag;
+use autopilot::mouse::{Button, ScrollDirection};
 use serde_with::{SerializeAs, DeserializeAs, serde_as};
 use image::io::Reader as ImageReader;
-use image::DynamicImage;
+use image::{DynamicImage, RgbaImage};
 
-use super::{MacroStep, ClickPoint, Settings, Macro};
+use super::{MacroStep, ClickPoint, ClickOptions, MatchMode, ColorCheck, Anchor, ImageTarget, Drag, DragEnd, Key, Launch, Shell, VariableCheck, Settings, Macro};
 
 #[derive(Serialize, Deserialize)]
 pub struct MacroSerializable {
@@ -54,17 +54,17 @@ impl MacroSerializable {
 #[serde_as]
 #[derive(Serialize, Deserialize)]
 pub enum MacroStepSerializable {
-    Launch(String),                         // has the command
-    ClickImage(Vec<u8>, ClickPoint, f32),    // image name, click point, allowed difference
-    MoveToImage(Vec<u8>, ClickPoint, f32),   // image name, click point which is a move point here, allowed difference
+    Launch(LaunchSerializable),
+    // the fields of the image target stay a tuple, saved files store them in this order
+    ClickImage(ImagesSerializable, ClickPoint, f32, #[serde(default)] MatchMode, #[serde(default)] Option<AnchorSerializable>, #[serde(default)] ClickOptionsSerializable),    // alternative images, click point, allowed difference, what is compared, image the search is limited around, button and count
+    MoveToImage(ImagesSerializable, ClickPoint, f32, #[serde(default)] MatchMode, #[serde(default)] Option<AnchorSerializable>),   // alternative images, click point which is a move point here, allowed difference, what is compared, image the search is limited around
     TypeText(
         String,
         #[serde_as(as = "Vec<FlagSerializable>")]
         Vec<Flag>
     ),
     PressKey(
-        #[serde_as(as = "KeyCodeSerializable")]
-        KeyCode,
+        Key,                // unit variants are named like autopilot's KeyCode, so older files still load
         #[serde_as(as = "Vec<FlagSerializable>")]
         Vec<Flag>
     ),
@@ -73,132 +73,365 @@ pub enum MacroStepSerializable {
         ScrollDirection,
         u32
     ),
-    WaitTime(u64)
+    WaitTime(u64),
+    WaitImageGone(Vec<u8>, f32, MatchMode, u64),    // image name, allowed difference, what is compared, how long it has to stay gone in ms
+    WaitColor(ColorCheckSerializable),
+    CheckColor(ColorCheckSerializable, u32),        // the check, how many of the next steps are skipped when the color doesn't match
+    MoveTo(i32, i32),
+    MoveBy(i32, i32),
+    ClickAt(i32, i32, #[serde(default)] ClickOptionsSerializable),
+    Drag(DragSerializable),
+    KeyDown(Key),
+    KeyUp(Key),
+    HoldKey(Key, u64),
+    RunShell(Shell),
+    SetClipboardText(String),
+    SetClipboardImage(Vec<u8>),
+    ReadClipboard(String),
+    WaitClipboardChange,
+    CheckVariable(VariableCheck)
 }
 
 impl MacroStepSerializable {
     pub fn to_normal(self) -> Result<MacroStep> {
         match self {
-            MacroStepSerializable::Launch(command) => Ok(MacroStep::Launch(command)),
-            MacroStepSerializable::ClickImage(image, click_point, allowed_difference) => {
-                let parsed_image = ImageReader::new(Cursor::new(image))
-                                                                .with_guessed_format()?
-                                                                .decode()?
-                                                                .into_rgb();
-
-                Ok(MacroStep::ClickImage(Some(parsed_image), click_point, allowed_difference))
-            },
-            MacroStepSerializable::MoveToImage(image, move_point, allowed_difference) => {
-                let parsed_image = ImageReader::new(Cursor::new(image))
-                                                                .with_guessed_format()?
-                                                                .decode()?
-                                                                .into_rgb();
-
-                Ok(MacroStep::MoveToImage(Some(parsed_image), move_point, allowed_difference))
-            },
+            MacroStepSerializable::Launch(launch) => Ok(MacroStep::Launch(launch.to_normal())),
+            MacroStepSerializable::ClickImage(images, click_point, allowed_difference, mode, anchor, options) => Ok(MacroStep::ClickImage(image_target(images, click_point, allowed_difference, mode, anchor)?, options.to_normal())),
+            MacroStepSerializable::MoveToImage(images, move_point, allowed_difference, mode, anchor) => Ok(MacroStep::MoveToImage(image_target(images, move_point, allowed_difference, mode, anchor)?)),
             MacroStepSerializable::TypeText(text, flags) => Ok(MacroStep::TypeText(text, flags)),
             MacroStepSerializable::PressKey(key, flags) => Ok(MacroStep::PressKey(key, flags)),
             MacroStepSerializable::Scroll(direction, amount) => Ok(MacroStep::Scroll(direction, amount)),
             MacroStepSerializable::WaitTime(time) => Ok(MacroStep::WaitTime(time)),
+            MacroStepSerializable::WaitImageGone(image, allowed_difference, mode, settle_time) => Ok(MacroStep::WaitImageGone(Some(decode_image(image)?), allowed_difference, mode, settle_time)),
+            MacroStepSerializable::WaitColor(check) => Ok(MacroStep::WaitColor(check.to_normal()?)),
+            MacroStepSerializable::CheckColor(check, skip_count) => Ok(MacroStep::CheckColor(check.to_normal()?, skip_count)),
+            MacroStepSerializable::MoveTo(x, y) => Ok(MacroStep::MoveTo(x, y)),
+            MacroStepSerializable::MoveBy(x, y) => Ok(MacroStep::MoveBy(x, y)),
+            MacroStepSerializable::ClickAt(x, y, options) => Ok(MacroStep::ClickAt(x, y, options.to_normal())),
+            MacroStepSerializable::Drag(drag) => Ok(MacroStep::Drag(drag.to_normal()?)),
+            MacroStepSerializable::KeyDown(key) => Ok(MacroStep::KeyDown(key)),
+            MacroStepSerializable::KeyUp(key) => Ok(MacroStep::KeyUp(key)),
+            MacroStepSerializable::HoldKey(key, time) => Ok(MacroStep::HoldKey(key, time)),
+            MacroStepSerializable::RunShell(shell) => Ok(MacroStep::RunShell(shell)),
+            MacroStepSerializable::SetClipboardText(text) => Ok(MacroStep::SetClipboardText(text)),
+            MacroStepSerializable::SetClipboardImage(image) => Ok(MacroStep::SetClipboardImage(Some(decode_image(image)?))),
+            MacroStepSerializable::ReadClipboard(name) => Ok(MacroStep::ReadClipboard(name)),
+            MacroStepSerializable::WaitClipboardChange => Ok(MacroStep::WaitClipboardChange),
+            MacroStepSerializable::CheckVariable(check) => Ok(MacroStep::CheckVariable(check)),
         }
     }
 
     pub fn from_normal(macro_step: MacroStep) -> Result<Self> {
         match macro_step {
-            MacroStep::Launch(command) => Ok(Self::Launch(command)),
-            MacroStep::ClickImage(image, click_point, allowed_difference) => {
-                let mut byte_image: Vec<u8> = Vec::new();
-
-                DynamicImage::ImageRgb8(
-                    image.ok_or(anyhow!("Missing image data"))?
-                ).write_to(&mut Cursor::new(&mut byte_image), image::ImageFormat::PNG)?;
-                        
-
-                Ok(Self::ClickImage(byte_image, click_point, allowed_difference))
-            },
-            MacroStep::MoveToImage(image, move_point, allowed_difference) => {
-                let mut byte_image: Vec<u8> = Vec::new();
-
-                DynamicImage::ImageRgb8(
-                    image.ok_or(anyhow!("Missing image data"))?
-                ).write_to(&mut Cursor::new(&mut byte_image), image::ImageFormat::PNG)?;
-
-                Ok(Self::MoveToImage(byte_image, move_point, allowed_difference))
-            },
+            MacroStep::Launch(launch) => Ok(Self::Launch(LaunchSerializable::Options(launch))),
+            MacroStep::ClickImage(ImageTarget { images, point, allowed_diff, mode, anchor }, options) => Ok(Self::ClickImage(ImagesSerializable::from_normal(images, anchor.is_some())?, point, allowed_diff, mode, anchor.map(AnchorSerializable::from_normal).transpose()?, ClickOptionsSerializable::from_normal(options))),
+            MacroStep::MoveToImage(ImageTarget { images, point, allowed_diff, mode, anchor }) => Ok(Self::MoveToImage(ImagesSerializable::from_normal(images, anchor.is_some())?, point, allowed_diff, mode, anchor.map(AnchorSerializable::from_normal).transpose()?)),
             MacroStep::TypeText(text, flags) => Ok(Self::TypeText(text, flags)),
             MacroStep::PressKey(key, flags) => Ok(Self::PressKey(key, flags)),
             MacroStep::Scroll(direction, amount) => Ok(Self::Scroll(direction, amount)),
             MacroStep::WaitTime(time) => Ok(Self::WaitTime(time)),
+            MacroStep::WaitImageGone(image, allowed_difference, mode, settle_time) => Ok(Self::WaitImageGone(encode_image(image.ok_or(anyhow!("Missing image data"))?)?, allowed_difference, mode, settle_time)),
+            MacroStep::WaitColor(check) => Ok(Self::WaitColor(ColorCheckSerializable::from_normal(check)?)),
+            MacroStep::CheckColor(check, skip_count) => Ok(Self::CheckColor(ColorCheckSerializable::from_normal(check)?, skip_count)),
+            MacroStep::MoveTo(x, y) => Ok(Self::MoveTo(x, y)),
+            MacroStep::MoveBy(x, y) => Ok(Self::MoveBy(x, y)),
+            MacroStep::ClickAt(x, y, options) => Ok(Self::ClickAt(x, y, ClickOptionsSerializable::from_normal(options))),
+            MacroStep::Drag(drag) => Ok(Self::Drag(DragSerializable::from_normal(drag)?)),
+            MacroStep::KeyDown(key) => Ok(Self::KeyDown(key)),
+            MacroStep::KeyUp(key) => Ok(Self::KeyUp(key)),
+            MacroStep::HoldKey(key, time) => Ok(Self::HoldKey(key, time)),
+            MacroStep::RunShell(shell) => Ok(Self::RunShell(shell)),
+            MacroStep::SetClipboardText(text) => Ok(Self::SetClipboardText(text)),
+            MacroStep::SetClipboardImage(image) => Ok(Self::SetClipboardImage(encode_image(image.ok_or(anyhow!("Missing image data"))?)?)),
+            MacroStep::ReadClipboard(name) => Ok(Self::ReadClipboard(name)),
+            MacroStep::WaitClipboardChange => Ok(Self::WaitClipboardChange),
+            MacroStep::CheckVariable(check) => Ok(Self::CheckVariable(check)),
         }
     }
 }
 
+/// files from before launches had options only store the command
 #[derive(Serialize, Deserialize)]
-#[serde(remote = "KeyCode")]
-enum KeyCodeSerializable {
-    F1,
-    F2,
-    F3,
-    F4,
-    F5,
-    F6,
-    F7,
-    F8,
-    F9,
-    F10,
-    F11,
-    F12,
-    F13,
-    F14,
-    F15,
-    F16,
-    F17,
-    F18,
-    F19,
-    F20,
-    F21,
-    F22,
-    F23,
-    F24,
-    LeftArrow,
-    Control,
-    RightArrow,
-    DownArrow,
-    End,
-    UpArrow,
-    PageUp,
-    Alt,
-    Return,
-    PageDown,
-    Delete,
-    Home,
-    Escape,
-    Backspace,
-    Meta,
-    CapsLock,
-    Shift,
-    Tab,
-    Space,
+#[serde(untagged)]
+pub enum LaunchSerializable {
+    Options(Launch),
+    Command(String)
 }
 
-impl SerializeAs<KeyCode> for KeyCodeSerializable {
-    fn serialize_as<S>(source: &KeyCode, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer
-    {
-        KeyCodeSerializable::serialize(source, serializer)
+impl LaunchSerializable {
+    pub fn to_normal(self) -> Launch {
+        match self {
+            LaunchSerializable::Options(launch) => launch,
+            LaunchSerializable::Command(command) => Launch::from_command(command),
+        }
     }
 }
 
-impl<'de> DeserializeAs<'de, KeyCode> for KeyCodeSerializable {
-    fn deserialize_as<D>(deserializer: D) -> Result<KeyCode, D::Error>
-    where
-        D: serde::Deserializer<'de>
-    {
-        KeyCodeSerializable::deserialize(deserializer)
+/// files from before steps had alternative images store a single image, new files always store a list
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ImagesSerializable {
+    Many(Vec<Vec<u8>>),     // has to be tried first, an empty list would also read as an empty single image
+    Single(Vec<u8>)
+}
+
+impl ImagesSerializable {
+    pub fn to_normal(self) -> Result<Vec<RgbaImage>> {
+        match self {
+            ImagesSerializable::Many(images) => images.into_iter().map(decode_image).collect(),
+            ImagesSerializable::Single(image) => Ok(vec![decode_image(image)?]),
+        }
+    }
+
+    /// a step with an anchor doesn't need images of its own, it targets the anchor then
+    pub fn from_normal(images: Vec<RgbaImage>, has_anchor: bool) -> Result<Self> {
+        if images.is_empty() && !has_anchor {
+            return Err(anyhow!("Missing image data"));
+        }
+
+        Ok(ImagesSerializable::Many(images.into_iter().map(encode_image).collect::<Result<_>>()?))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ColorCheckSerializable {
+    pub relative_to_image: bool,
+    pub image: Option<Vec<u8>>,
+    pub image_allowed_diff: f32,
+    pub image_mode: MatchMode,
+    pub x: i32,
+    pub y: i32,
+    pub size: u32,
+    pub color: [u8; 3],
+    pub allowed_diff: f32
+}
+
+impl ColorCheckSerializable {
+    pub fn to_normal(self) -> Result<ColorCheck> {
+        let image = match self.image {
+            Some(image) => Some(decode_image(image)?),
+            None => None,
+        };
+
+        Ok(ColorCheck {
+            relative_to_image: self.relative_to_image,
+            image,
+            image_allowed_diff: self.image_allowed_diff,
+            image_mode: self.image_mode,
+            x: self.x,
+            y: self.y,
+            size: self.size,
+            color: self.color,
+            allowed_diff: self.allowed_diff
+        })
+    }
+
+    pub fn from_normal(check: ColorCheck) -> Result<Self> {
+        // an image picked before switching to absolute coordinates is kept, so switching back doesn't lose it
+        let image = match check.image {
+            Some(image) => Some(encode_image(image)?),
+            None => None,
+        };
+
+        Ok(Self {
+            relative_to_image: check.relative_to_image,
+            image,
+            image_allowed_diff: check.image_allowed_diff,
+            image_mode: check.image_mode,
+            x: check.x,
+            y: check.y,
+            size: check.size,
+            color: check.color,
+            allowed_diff: check.allowed_diff
+        })
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct AnchorSerializable {
+    pub image: Vec<u8>,
+    pub allowed_diff: f32,
+    pub mode: MatchMode,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32
+}
+
+impl AnchorSerializable {
+    pub fn to_normal(self) -> Result<Anchor> {
+        Ok(Anchor {
+            image: Some(decode_image(self.image)?),
+            allowed_diff: self.allowed_diff,
+            mode: self.mode,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height
+        })
+    }
+
+    pub fn from_normal(anchor: Anchor) -> Result<Self> {
+        Ok(Self {
+            image: encode_image(anchor.image.ok_or(anyhow!("Missing anchor image data"))?)?,
+            allowed_diff: anchor.allowed_diff,
+            mode: anchor.mode,
+            x: anchor.x,
+            y: anchor.y,
+            width: anchor.width,
+            height: anchor.height
+        })
+    }
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct DragSerializable {
+    pub source: DragEndSerializable,
+    pub destination: DragEndSerializable,
+    #[serde_as(as = "ButtonSerializable")]
+    pub button: Button,
+    pub press_hold_ms: u64,
+    pub move_ms: u64,
+    pub release_hold_ms: u64
+}
+
+impl DragSerializable {
+    pub fn to_normal(self) -> Result<Drag> {
+        Ok(Drag {
+            source: self.source.to_normal()?,
+            destination: self.destination.to_normal()?,
+            button: self.button,
+            press_hold_ms: self.press_hold_ms,
+            move_ms: self.move_ms,
+            release_hold_ms: self.release_hold_ms
+        })
+    }
+
+    pub fn from_normal(drag: Drag) -> Result<Self> {
+        Ok(Self {
+            source: DragEndSerializable::from_normal(drag.source)?,
+            destination: DragEndSerializable::from_normal(drag.destination)?,
+            button: drag.button,
+            press_hold_ms: drag.press_hold_ms,
+            move_ms: drag.move_ms,
+            release_hold_ms: drag.release_hold_ms
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DragEndSerializable {
+    pub on_image: bool,
+    pub image: Option<Vec<u8>>,
+    pub point: ClickPoint,
+    pub allowed_diff: f32,
+    pub mode: MatchMode,
+    pub x: i32,
+    pub y: i32
+}
+
+impl DragEndSerializable {
+    pub fn to_normal(self) -> Result<DragEnd> {
+        let image = match self.image {
+            Some(image) => Some(decode_image(image)?),
+            None => None,
+        };
+
+        Ok(DragEnd {
+            on_image: self.on_image,
+            image,
+            point: self.point,
+            allowed_diff: self.allowed_diff,
+            mode: self.mode,
+            x: self.x,
+            y: self.y
+        })
+    }
+
+    pub fn from_normal(end: DragEnd) -> Result<Self> {
+        if end.on_image && end.image.is_none() {
+            return Err(anyhow!("Missing image data"));
+        }
+
+        let image = match end.image {
+            Some(image) => Some(encode_image(image)?),
+            None => None,
+        };
+
+        Ok(Self {
+            on_image: end.on_image,
+            image,
+            point: end.point,
+            allowed_diff: end.allowed_diff,
+            mode: end.mode,
+            x: end.x,
+            y: end.y
+        })
+    }
+}
+
+/// steps from before click options existed did a single left click
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct ClickOptionsSerializable {
+    #[serde_as(as = "ButtonSerializable")]
+    pub button: Button,
+    pub count: u32,
+    #[serde_as(as = "Vec<FlagSerializable>")]
+    pub modifiers: Vec<Flag>
+}
+
+impl Default for ClickOptionsSerializable {
+    fn default() -> Self {
+        Self::from_normal(Default::default())
+    }
+}
+
+impl ClickOptionsSerializable {
+    pub fn to_normal(self) -> ClickOptions {
+        ClickOptions {
+            button: self.button,
+            count: self.count,
+            modifiers: self.modifiers
+        }
+    }
+
+    pub fn from_normal(options: ClickOptions) -> Self {
+        Self {
+            button: options.button,
+            count: options.count,
+            modifiers: options.modifiers
+        }
+    }
+}
+
+fn image_target(images: ImagesSerializable, point: ClickPoint, allowed_diff: f32, mode: MatchMode, anchor: Option<AnchorSerializable>) -> Result<ImageTarget> {
+    Ok(ImageTarget {
+        images: images.to_normal()?,
+        point,
+        allowed_diff,
+        mode,
+        anchor: anchor.map(AnchorSerializable::to_normal).transpose()?
+    })
+}
+
+/// images are stored as PNG bytes
+fn decode_image(bytes: Vec<u8>) -> Result<RgbaImage> {
+    Ok(ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .decode()?
+        .into_rgba())
+}
+
+fn encode_image(image: RgbaImage) -> Result<Vec<u8>> {
+    let mut byte_image: Vec<u8> = Vec::new();
+
+    DynamicImage::ImageRgba8(image).write_to(&mut Cursor::new(&mut byte_image), image::ImageFormat::PNG)?;
+
+    Ok(byte_image)
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "ScrollDirection")]
 enum ScrollDirectionSerializable {
@@ -224,6 +457,32 @@ impl<'de> DeserializeAs<'de, ScrollDirection> for ScrollDirectionSerializable {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Button")]
+enum ButtonSerializable {
+    Left,
+    Middle,
+    Right,
+}
+
+impl SerializeAs<Button> for ButtonSerializable {
+    fn serialize_as<S>(source: &Button, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        ButtonSerializable::serialize(source, serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Button> for ButtonSerializable {
+    fn deserialize_as<D>(deserializer: D) -> Result<Button, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        ButtonSerializable::deserialize(deserializer)
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "Flag")]
 enum FlagSerializable {
@@ -250,4 +509,244 @@ impl<'de> DeserializeAs<'de, Flag> for FlagSerializable {
     {
         FlagSerializable::deserialize(deserializer)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use autopilot::key::Flag;
+    use autopilot::mouse::{Button, ScrollDirection};
+    use image::{DynamicImage, RgbImage, RgbaImage, Rgb, Rgba};
+    use serde::Serialize;
+
+    use super::{MacroSerializable, MacroStepSerializable};
+    use crate::macro_logic::{
+        Macro, MacroStep, ClickPoint, OffsetUnit, ClickOptions, MatchMode, ImageTarget, Anchor, ColorCheck, Drag, DragEnd,
+        Key, Launch, Shell, VariableCheck, Comparison, CheckFailure, Settings
+    };
+
+    // the shapes files were saved with before any of the new steps and options existed
+
+    #[derive(Serialize)]
+    struct BaselineMacro {
+        macro_name: String,
+        settings: BaselineSettings,
+        macro_steps: Vec<BaselineStep>
+    }
+
+    #[derive(Serialize)]
+    struct BaselineSettings {
+        step_timeout_seconds: u64,
+        break_whole_macro: bool
+    }
+
+    #[derive(Serialize)]
+    enum BaselineStep {
+        Launch(String),
+        ClickImage(Vec<u8>, ClickPoint, f32),
+        MoveToImage(Vec<u8>, ClickPoint, f32),
+        PressKey(BaselineKeyCode, Vec<BaselineFlag>)
+    }
+
+    #[derive(Serialize)]
+    enum BaselineKeyCode {
+        F5,
+        Return
+    }
+
+    #[derive(Serialize)]
+    enum BaselineFlag {
+        Control
+    }
+
+    fn baseline_png() -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(3, 2, Rgb([200, 0, 0])))
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::PNG)
+            .unwrap();
+
+        bytes
+    }
+
+    // RGB images of old files load as opaque ones
+    fn assert_baseline_image(images: &[RgbaImage]) {
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].dimensions(), (3, 2));
+        assert!(images[0].pixels().all(|pixel| *pixel == Rgba([200, 0, 0, 255])));
+    }
+
+    fn image(color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(3, 2, Rgba(color))
+    }
+
+    fn load_baseline(steps: Vec<BaselineStep>) -> Macro {
+        let baseline = BaselineMacro {
+            macro_name: "old".into(),
+            settings: BaselineSettings { step_timeout_seconds: 30, break_whole_macro: true },
+            macro_steps: steps
+        };
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&baseline, &mut bytes).unwrap();
+
+        ciborium::de::from_reader::<MacroSerializable, _>(bytes.as_slice()).unwrap().to_normal().unwrap()
+    }
+
+    /// saves and loads the step the way a macro file does
+    fn round_trip(step: MacroStep) -> MacroStep {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&MacroStepSerializable::from_normal(step).unwrap(), &mut bytes).unwrap();
+
+        ciborium::de::from_reader::<MacroStepSerializable, _>(bytes.as_slice()).unwrap().to_normal().unwrap()
+    }
+
+    // the steps don't implement PartialEq, images included they print every field though
+    fn assert_round_trips(step: MacroStep) {
+        assert_eq!(format!("{:?}", round_trip(step.clone())), format!("{:?}", step));
+    }
+
+    #[test]
+    fn baseline_image_steps_load() {
+        let steps = load_baseline(vec![
+            BaselineStep::ClickImage(baseline_png(), ClickPoint::BottomRight, 0.25),
+            BaselineStep::MoveToImage(baseline_png(), ClickPoint::TopLeft, 0.5)
+        ]).macro_steps;
+
+        match &steps[0] {
+            MacroStep::ClickImage(ImageTarget { images, point: ClickPoint::BottomRight, allowed_diff, mode: MatchMode::Exact, anchor: None }, ClickOptions { button: Button::Left, count: 1, modifiers }) => {
+                assert_baseline_image(images);
+                assert_eq!(*allowed_diff, 0.25);
+                assert!(modifiers.is_empty());
+            },
+            step => panic!("loaded as {:?}", step),
+        }
+
+        match &steps[1] {
+            MacroStep::MoveToImage(ImageTarget { images, point: ClickPoint::TopLeft, allowed_diff, mode: MatchMode::Exact, anchor: None }) => {
+                assert_baseline_image(images);
+                assert_eq!(*allowed_diff, 0.5);
+            },
+            step => panic!("loaded as {:?}", step),
+        }
+    }
+
+    #[test]
+    fn baseline_key_and_launch_steps_load() {
+        let baseline = load_baseline(vec![
+            BaselineStep::PressKey(BaselineKeyCode::Return, vec![BaselineFlag::Control]),
+            BaselineStep::PressKey(BaselineKeyCode::F5, vec![]),
+            BaselineStep::Launch("firefox --new-window".into())
+        ]);
+
+        assert_eq!(baseline.macro_name, "old");
+        assert_eq!(baseline.settings.step_timeout_seconds, 30);
+        assert!(baseline.settings.break_whole_macro);
+        assert_eq!(baseline.settings.search_interval_ms, 300);
+
+        assert!(matches!(&baseline.macro_steps[0], MacroStep::PressKey(Key::Return, flags) if flags.len() == 1 && matches!(flags[0], Flag::Control)));
+        assert!(matches!(&baseline.macro_steps[1], MacroStep::PressKey(Key::F5, flags) if flags.is_empty()));
+
+        match &baseline.macro_steps[2] {
+            MacroStep::Launch(launch) => {
+                let expected = Launch::from_command("firefox --new-window".into());
+
+                assert_eq!(format!("{:?}", launch), format!("{:?}", expected));
+            },
+            step => panic!("loaded as {:?}", step),
+        }
+    }
+
+    #[test]
+    fn image_steps_round_trip() {
+        let anchor = Anchor { image: Some(image([0, 0, 255, 255])), allowed_diff: 0.1, mode: MatchMode::Edges, x: 10, y: -20, width: 300, height: 40 };
+        let options = ClickOptions { button: Button::Right, count: 2, modifiers: vec![Flag::Control, Flag::Shift] };
+
+        for point in [ClickPoint::CenterMiddle, ClickPoint::Offset(40.0, -12.5, OffsetUnit::Pixels), ClickPoint::Offset(0.5, 1.25, OffsetUnit::Fraction)] {
+            let target = ImageTarget { images: vec![image([200, 0, 0, 255]), image([0, 200, 0, 0])], point, allowed_diff: 0.3, mode: MatchMode::Grayscale, anchor: None };
+
+            assert_round_trips(MacroStep::ClickImage(target.clone(), options.clone()));
+            assert_round_trips(MacroStep::MoveToImage(target));
+        }
+
+        // with an anchor the images of the step itself can be left out
+        let target = ImageTarget { images: vec![], point: ClickPoint::TopRight, allowed_diff: 0.0, mode: MatchMode::Exact, anchor: Some(anchor) };
+
+        assert_round_trips(MacroStep::ClickImage(target.clone(), ClickOptions::default()));
+        assert_round_trips(MacroStep::MoveToImage(target));
+
+        assert_round_trips(MacroStep::WaitImageGone(Some(image([1, 2, 3, 255])), 0.2, MatchMode::ColorInsensitive, 500));
+        assert_round_trips(MacroStep::SetClipboardImage(Some(image([1, 2, 3, 128]))));
+    }
+
+    #[test]
+    fn image_steps_without_images_dont_save() {
+        assert!(MacroStepSerializable::from_normal(MacroStep::ClickImage(ImageTarget::default(), ClickOptions::default())).is_err());
+        assert!(MacroStepSerializable::from_normal(MacroStep::WaitImageGone(None, 0.0, MatchMode::Exact, 0)).is_err());
+    }
+
+    #[test]
+    fn launch_round_trips() {
+        let launch = Launch {
+            program: "song.mp3".into(),
+            arguments: "--volume 50".into(),
+            working_dir: "/tmp".into(),
+            env: vec![("LANG".into(), "C".into())],
+            wait: true,
+            timeout_seconds: 5,
+            check_exit_code: true,
+            open_with_default: true
+        };
+
+        assert_round_trips(MacroStep::Launch(launch));
+    }
+
+    #[test]
+    fn key_steps_round_trip() {
+        assert_round_trips(MacroStep::PressKey(Key::Character('t'), vec![Flag::Control, Flag::Shift]));
+        assert_round_trips(MacroStep::PressKey(Key::NumpadAdd, vec![]));
+        assert_round_trips(MacroStep::KeyDown(Key::Shift));
+        assert_round_trips(MacroStep::KeyUp(Key::Character(',')));
+        assert_round_trips(MacroStep::HoldKey(Key::Space, 1500));
+    }
+
+    #[test]
+    fn other_steps_round_trip() {
+        let check = ColorCheck { relative_to_image: true, image: Some(image([9, 9, 9, 255])), image_allowed_diff: 0.1, image_mode: MatchMode::Grayscale, x: 4, y: -3, size: 5, color: [10, 20, 30], allowed_diff: 0.05 };
+        let drag = Drag {
+            source: DragEnd { on_image: true, image: Some(image([50, 60, 70, 255])), point: ClickPoint::Offset(-5.0, 8.0, OffsetUnit::Pixels), allowed_diff: 0.2, mode: MatchMode::Edges, x: 0, y: 0 },
+            destination: DragEnd { on_image: false, image: None, point: ClickPoint::default(), allowed_diff: 0.0, mode: MatchMode::Exact, x: 800, y: 600 },
+            button: Button::Middle,
+            press_hold_ms: 100,
+            move_ms: 250,
+            release_hold_ms: 300
+        };
+
+        assert_round_trips(MacroStep::TypeText("hello ${name}".into(), vec![Flag::Alt]));
+        assert_round_trips(MacroStep::Scroll(ScrollDirection::Down, 3));
+        assert_round_trips(MacroStep::WaitTime(250));
+        assert_round_trips(MacroStep::WaitColor(check.clone()));
+        assert_round_trips(MacroStep::CheckColor(ColorCheck { relative_to_image: false, ..check }, 2));
+        assert_round_trips(MacroStep::MoveTo(-100, 200));
+        assert_round_trips(MacroStep::MoveBy(5, -5));
+        assert_round_trips(MacroStep::ClickAt(10, 20, ClickOptions { button: Button::Middle, count: 3, modifiers: vec![Flag::Meta] }));
+        assert_round_trips(MacroStep::Drag(drag));
+        assert_round_trips(MacroStep::RunShell(Shell { command: "echo hi".into(), stdout_variable: "out".into(), stderr_variable: "err".into(), status_variable: "status".into(), timeout_seconds: 7 }));
+        assert_round_trips(MacroStep::SetClipboardText("AB-${number}".into()));
+        assert_round_trips(MacroStep::ReadClipboard("copied".into()));
+        assert_round_trips(MacroStep::WaitClipboardChange);
+        assert_round_trips(MacroStep::CheckVariable(VariableCheck { name: "output".into(), comparison: Comparison::Matches, value: r"^\d+$".into(), on_fail: CheckFailure::SkipNext(2) }));
+    }
+
+    #[test]
+    fn macro_round_trips() {
+        let macro_data = Macro::new("new".into(), Settings { search_interval_ms: 100, opener: "xdg-open".into(), ..Default::default() }, vec![MacroStep::WaitTime(1)]);
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&MacroSerializable::from_normal(macro_data.clone()).unwrap(), &mut bytes).unwrap();
+        let loaded = ciborium::de::from_reader::<MacroSerializable, _>(bytes.as_slice()).unwrap().to_normal().unwrap();
+
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", macro_data));
+    }
+}
@@ -1,19 +1,28 @@
 use serde::{Serialize, Deserialize};
 
+use super::screen::MonitorChoice;
+use super::window_target::WindowTarget;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub step_timeout_seconds: u64,
-    pub break_whole_macro: bool
+    pub break_whole_macro: bool,
+    #[serde(default = "default_search_interval_ms")]
+    pub search_interval_ms: u64,        // pause between screenshots of waiting steps, never shorter than the search itself took
+    #[serde(default)]
+    pub monitor: MonitorChoice,         // where the image steps search, older macros only knew the primary monitor
+    #[serde(default)]
+    pub window: Option<WindowTarget>,   // searches only in this window instead of the monitors and makes positions relative to it
+    #[serde(default)]
+    pub opener: String                  // command line that opens files with their default application, empty tries the usual ones
+}
+
+fn default_search_interval_ms() -> u64 {
+    300
 }
 
 impl Default for Settings {
     fn default() -> Self {
-        Self { step_timeout_seconds: 60, break_whole_macro: true }
+        Self { step_timeout_seconds: 60, break_whole_macro: true, search_interval_ms: default_search_interval_ms(), monitor: Default::default(), window: None, opener: String::new() }
     }
 }
-
-impl Settings {
-    pub fn new(step_timeout_seconds: u64, break_whole_macro: bool) -> Self {
-        Settings { step_timeout_seconds, break_whole_macro }
-    }
-}
\ No newline at end of file
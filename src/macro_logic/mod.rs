@@ -1,10 +1,44 @@
 mod util;
+mod image_search;
+mod color_check;
+mod drag;
+mod launch;
+mod shell;
+mod clipboard;
+mod variables;
+mod variable_check;
+mod keys;
+mod hotkey;
+mod anchor;
+mod image_target;
+mod calibration;
+mod screen;
+mod window_target;
+mod template_edit;
 mod macro_base;
 mod macro_serde;
 mod macro_settings;
 
 pub use macro_base::Macro;
 pub use macro_base::MacroStep;
+pub use macro_base::ImageSlot;
 pub use macro_base::ClickPoint;
+pub use macro_base::ClickOptions;
+pub use macro_base::OffsetUnit;
+pub use image_search::MatchMode;
+pub use image_search::{is_opaque, has_opaque_pixels, Candidate};
+pub use color_check::ColorCheck;
+pub use drag::{Drag, DragEnd};
+pub use launch::Launch;
+pub use shell::Shell;
+pub use variable_check::{VariableCheck, Comparison, CheckFailure};
+pub use keys::{Key, parse_shortcut, format_shortcut};
+pub use hotkey::wait_for_f8;
+pub use anchor::Anchor;
+pub use image_target::ImageTarget;
+pub use calibration::Calibration;
+pub use screen::{capture_for, relative_cursor_position, MonitorChoice};
+pub use window_target::WindowTarget;
+pub use template_edit::{crop, auto_trim, set_mask};
 pub use util::EnumInterString;
 pub use macro_settings::Settings;
\ No newline at end of file
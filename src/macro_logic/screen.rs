@@ -0,0 +1,321 @@
+use anyhow::{Result, anyhow};
+use autopilot::{mouse, geometry::Point};
+use image::{RgbaImage, Rgba, imageops};
+use serde::{Serialize, Deserialize};
+use xcap::Monitor;
+
+use super::Settings;
+use super::template_edit;
+
+/// which monitors the image and color steps look at
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MonitorChoice {
+    #[default]
+    Primary,            // the only behavior before monitors could be chosen
+    Index(usize),       // counted from the left, the same order the settings list them in
+    All,                // the whole virtual desktop, gaps between monitors are black
+    UnderCursor         // wherever the mouse is when the step starts searching
+}
+
+/// a rectangle of the virtual desktop in physical pixels, the primary monitor starts at 0, 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32
+}
+
+impl Bounds {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width as i32 && y < self.y + self.height as i32
+    }
+
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        let (left, top) = (self.x.min(other.x), self.y.min(other.y));
+        let right = (self.x + self.width as i32).max(other.x + other.width as i32);
+        let bottom = (self.y + self.height as i32).max(other.y + other.height as i32);
+
+        Bounds { x: left, y: top, width: (right - left) as u32, height: (bottom - top) as u32 }
+    }
+
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let (left, top) = (self.x.max(other.x), self.y.max(other.y));
+        let right = (self.x + self.width as i32).min(other.x + other.width as i32);
+        let bottom = (self.y + self.height as i32).min(other.y + other.height as i32);
+
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some(Bounds { x: left, y: top, width: (right - left) as u32, height: (bottom - top) as u32 })
+    }
+}
+
+/// a screenshot of some part of the virtual desktop, with what's needed to turn its pixels into mouse positions
+pub struct Capture {
+    pub image: RgbaImage,
+    pub left: i32,          // where the top left pixel of the image is on the virtual desktop
+    pub top: i32,
+    pub scale: f64          // physical pixels per desktop point, autopilot uses a single one for every monitor
+}
+
+impl Capture {
+    /// desktop coordinates of a pixel of the image - the space the mouse moves in and the positions in macros are stored in
+    pub fn to_desktop(&self, x: f64, y: f64) -> (f64, f64) {
+        ((self.left as f64 + x) / self.scale, (self.top as f64 + y) / self.scale)
+    }
+
+    /// the pixel of the image at desktop coordinates, can be outside of the image
+    pub fn to_pixel(&self, x: f64, y: f64) -> (i64, i64) {
+        ((x * self.scale).round() as i64 - self.left as i64, (y * self.scale).round() as i64 - self.top as i64)
+    }
+
+    /// the part of the capture inside of a rectangle of the desktop, None if they don't overlap
+    pub fn crop(&self, bounds: &Bounds) -> Option<Capture> {
+        let own = Bounds { x: self.left, y: self.top, width: self.image.width(), height: self.image.height() };
+        let part = own.intersection(bounds)?;
+
+        let image = template_edit::crop(&self.image, (part.x - self.left) as u32, (part.y - self.top) as u32, part.width, part.height)?;
+
+        Some(Capture { image, left: part.x, top: part.y, scale: self.scale })
+    }
+}
+
+/// connected monitors from left to right, so the numbers in the settings stay the same between runs
+fn monitors() -> Result<Vec<Monitor>> {
+    let mut monitors = Monitor::all()?;
+
+    if monitors.is_empty() {
+        return Err(anyhow!("No monitors found"));
+    }
+
+    monitors.sort_by_key(|monitor| (monitor.x(), monitor.y()));
+
+    Ok(monitors)
+}
+
+fn bounds(monitor: &Monitor) -> Bounds {
+    Bounds { x: monitor.x(), y: monitor.y(), width: monitor.width(), height: monitor.height() }
+}
+
+pub fn monitor_count() -> usize {
+    monitors().map(|monitors| monitors.len()).unwrap_or(0)
+}
+
+/// takes a screenshot of the chosen monitors, the same picture the image steps search in
+pub fn capture(choice: MonitorChoice) -> Result<Capture> {
+    let monitors = monitors()?;
+    let scale = autopilot::screen::scale();
+
+    let chosen: Vec<&Monitor> = match choice {
+        MonitorChoice::Primary => vec![monitors.iter().find(|monitor| monitor.is_primary()).unwrap_or(&monitors[0])],
+        MonitorChoice::Index(index) => vec![monitors.get(index).ok_or(anyhow!("Monitor {} isn't connected", index + 1))?],
+        MonitorChoice::All => monitors.iter().collect(),
+        MonitorChoice::UnderCursor => {
+            let (x, y) = cursor_position();
+
+            vec![monitor_at(&monitors, x, y, scale).ok_or(anyhow!("The mouse cursor isn't on any monitor"))?]
+        },
+    };
+
+    let parts = chosen.into_iter()
+        .map(|monitor| Ok((bounds(monitor), to_rgba(monitor.capture_image()?)?)))
+        .collect::<Result<Vec<(Bounds, RgbaImage)>>>()?;
+
+    Ok(compose(parts, scale))
+}
+
+/// what the steps of a macro search in - its target window wherever it is right now, or the chosen monitors
+pub fn capture_for(settings: &Settings) -> Result<Capture> {
+    let window = match &settings.window {
+        Some(window) => window.locate()?,
+        None => return capture(settings.monitor),
+    };
+
+    capture(MonitorChoice::All)?.crop(&window).ok_or(anyhow!("The window is outside of the screen"))
+}
+
+/// desktop coordinates the positions of a macro are relative to, the top left corner of its target window
+/// or of the primary monitor
+pub fn origin(settings: &Settings) -> Result<(f64, f64)> {
+    let window = match &settings.window {
+        Some(window) => window.locate()?,
+        None => return Ok((0.0, 0.0)),
+    };
+
+    let scale = autopilot::screen::scale();
+
+    Ok((window.x as f64 / scale, window.y as f64 / scale))
+}
+
+/// the mouse position the way a macro stores positions, from its origin
+pub fn relative_cursor_position(settings: &Settings) -> Result<(f64, f64)> {
+    let (origin_x, origin_y) = origin(settings)?;
+    let (cursor_x, cursor_y) = cursor_position();
+
+    Ok((cursor_x - origin_x, cursor_y - origin_y))
+}
+
+/// takes a screenshot of the monitor with the given desktop coordinates on it
+pub fn capture_at(x: f64, y: f64) -> Result<Capture> {
+    let monitors = monitors()?;
+    let scale = autopilot::screen::scale();
+    let monitor = monitor_at(&monitors, x, y, scale).ok_or(anyhow!("The point {}, {} isn't on any monitor", x, y))?;
+
+    Ok(compose(vec![(bounds(monitor), to_rgba(monitor.capture_image()?)?)], scale))
+}
+
+fn monitor_at(monitors: &[Monitor], x: f64, y: f64, scale: f64) -> Option<&Monitor> {
+    let (x, y) = ((x * scale).round() as i32, (y * scale).round() as i32);
+
+    monitors.iter().find(|monitor| bounds(monitor).contains(x, y))
+}
+
+/// xcap uses a newer version of the image crate, the pixels are the same
+fn to_rgba(image: xcap::image::RgbaImage) -> Result<RgbaImage> {
+    let (width, height) = (image.width(), image.height());
+
+    RgbaImage::from_raw(width, height, image.into_raw()).ok_or(anyhow!("Failed to read the screenshot"))
+}
+
+/// puts the screenshots of single monitors where they are on the virtual desktop
+pub fn compose(mut parts: Vec<(Bounds, RgbaImage)>, scale: f64) -> Capture {
+    if parts.len() == 1 {
+        let (bounds, image) = parts.remove(0);
+
+        return Capture { image, left: bounds.x, top: bounds.y, scale };
+    }
+
+    let total = match parts.iter().map(|(bounds, _)| *bounds).reduce(|a, b| a.union(&b)) {
+        Some(total) => total,
+        None => return Capture { image: RgbaImage::new(0, 0), left: 0, top: 0, scale },
+    };
+
+    let mut image = RgbaImage::from_pixel(total.width, total.height, Rgba([0, 0, 0, 255]));
+
+    for (bounds, part) in parts.iter() {
+        imageops::replace(&mut image, part, (bounds.x - total.x) as u32, (bounds.y - total.y) as u32);
+    }
+
+    Capture { image, left: total.x, top: total.y, scale }
+}
+
+/// the mouse position in desktop coordinates
+pub fn cursor_position() -> (f64, f64) {
+    let location = mouse::location();
+
+    (location.x, location.y)
+}
+
+/// moves the mouse to desktop coordinates, autopilot refuses anything outside of the primary monitor
+/// so the other ones are reached through the system directly
+pub fn move_mouse(x: f64, y: f64) -> Result<()> {
+    let point = Point::new(x, y);
+
+    if autopilot::screen::is_point_visible(point) {
+        mouse::move_to(point)?;
+        return Ok(());
+    }
+
+    let scale = autopilot::screen::scale();
+
+    warp_mouse((x * scale).round() as i32, (y * scale).round() as i32)
+}
+
+#[cfg(target_os = "windows")]
+fn warp_mouse(x: i32, y: i32) -> Result<()> {
+    use winapi::um::winuser::SetCursorPos;
+
+    match unsafe { SetCursorPos(x, y) } {
+        0 => Err(anyhow!("Failed to move the mouse to {}, {}", x, y)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn warp_mouse(x: i32, y: i32) -> Result<()> {
+    use std::ptr;
+    use x11::xlib;
+
+    unsafe {
+        let display = xlib::XOpenDisplay(ptr::null());
+
+        if display.is_null() {
+            return Err(anyhow!("Failed to open the X display"));
+        }
+
+        xlib::XWarpPointer(display, 0, xlib::XDefaultRootWindow(display), 0, 0, 0, 0, x, y);
+        xlib::XFlush(display);
+        xlib::XCloseDisplay(display);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn warp_mouse(x: i32, y: i32) -> Result<()> {
+    Err(anyhow!("Moving the mouse to {}, {} outside of the primary monitor isn't supported on this system", x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::{Bounds, compose};
+
+    #[test]
+    fn monitors_are_placed_on_the_desktop() {
+        // a smaller monitor to the left of the primary one, lower by 100px
+        let left = (Bounds { x: -200, y: 100, width: 200, height: 100 }, RgbaImage::from_pixel(200, 100, Rgba([255, 0, 0, 255])));
+        let primary = (Bounds { x: 0, y: 0, width: 300, height: 200 }, RgbaImage::from_pixel(300, 200, Rgba([0, 0, 255, 255])));
+
+        let capture = compose(vec![left, primary], 1.0);
+
+        assert_eq!((capture.image.width(), capture.image.height()), (500, 200));
+        assert_eq!((capture.left, capture.top), (-200, 0));
+        assert_eq!(capture.image.get_pixel(0, 150), &Rgba([255, 0, 0, 255]));
+        assert_eq!(capture.image.get_pixel(0, 50), &Rgba([0, 0, 0, 255]));      // above the left monitor
+        assert_eq!(capture.image.get_pixel(200, 0), &Rgba([0, 0, 255, 255]));
+
+        // the top left pixel of the primary monitor is the origin of the desktop
+        assert_eq!(capture.to_desktop(200.0, 0.0), (0.0, 0.0));
+        assert_eq!(capture.to_desktop(10.0, 150.0), (-190.0, 150.0));
+        assert_eq!(capture.to_pixel(-190.0, 150.0), (10, 150));
+    }
+
+    #[test]
+    fn scaled_desktop_uses_points() {
+        let capture = compose(vec![(Bounds { x: 1920, y: 0, width: 100, height: 100 }, RgbaImage::new(100, 100))], 2.0);
+
+        assert_eq!(capture.image.width(), 100);
+        assert_eq!(capture.to_desktop(40.0, 20.0), (980.0, 10.0));
+        assert_eq!(capture.to_pixel(980.0, 10.0), (40, 20));
+    }
+
+    #[test]
+    fn capture_is_cropped_to_a_window() {
+        let image = RgbaImage::from_fn(300, 200, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        let capture = compose(vec![(Bounds { x: -100, y: 0, width: 300, height: 200 }, image)], 1.0);
+
+        // a window hanging over the bottom edge of the desktop
+        let window = capture.crop(&Bounds { x: -50, y: 150, width: 80, height: 100 }).unwrap();
+
+        assert_eq!((window.image.width(), window.image.height()), (80, 50));
+        assert_eq!((window.left, window.top), (-50, 150));
+        assert_eq!(window.image.get_pixel(0, 0), &Rgba([50, 150, 0, 255]));
+        assert_eq!(window.to_desktop(10.0, 5.0), (-40.0, 155.0));
+
+        assert!(capture.crop(&Bounds { x: 200, y: 0, width: 10, height: 10 }).is_none());
+    }
+
+    #[test]
+    fn bounds_contain_their_pixels_only() {
+        let bounds = Bounds { x: -10, y: 0, width: 10, height: 5 };
+
+        assert!(bounds.contains(-10, 0));
+        assert!(bounds.contains(-1, 4));
+        assert!(!bounds.contains(0, 0));
+        assert!(!bounds.contains(-5, 5));
+    }
+}
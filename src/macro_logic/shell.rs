@@ -0,0 +1,145 @@
+use std::io::Read;
+use std::process::{Command, Stdio, ExitStatus};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+
+use super::launch::Launch;
+use super::variables::Variables;
+
+/// runs a command line through the shell of the user and keeps what it printed in variables
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Shell {
+    pub command: String,
+    pub stdout_variable: String,    // empty ones aren't kept
+    pub stderr_variable: String,
+    pub status_variable: String,    // the exit code, empty when the command was ended without one
+    pub timeout_seconds: u64        // the command is killed after that
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            stdout_variable: "output".into(),
+            stderr_variable: String::new(),
+            status_variable: String::new(),
+            timeout_seconds: 60
+        }
+    }
+}
+
+impl Shell {
+    /// stores the output in the variables, false when the command didn't finish in time
+    pub fn run(&self, variables: &mut Variables) -> Result<bool> {
+        let mut child = shell_command(&self.command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| anyhow!("Failed to start the shell: {}", err))?;
+
+        // read while the command runs, a full pipe would block it forever
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+
+        let status = match Launch::wait_for_exit(&mut child, Duration::from_secs(self.timeout_seconds))? {
+            Some(status) => status,
+            None => {
+                // the readers are left behind, programs the command started could keep the pipes open
+                child.kill().ok();
+                return Ok(false);
+            },
+        };
+
+        variables.set(&self.stdout_variable, trim_line_break(stdout.join().unwrap_or_default()));
+        variables.set(&self.stderr_variable, trim_line_break(stderr.join().unwrap_or_default()));
+        variables.set(&self.status_variable, status_text(status));
+
+        Ok(true)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn shell_command(command_line: &str) -> Command {
+    let mut command = Command::new(std::env::var("COMSPEC").unwrap_or("cmd".into()));
+
+    command.args(["/C", command_line]);
+
+    command
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_command(command_line: &str) -> Command {
+    let mut command = Command::new(std::env::var("SHELL").unwrap_or("sh".into()));
+
+    command.args(["-c", command_line]);
+
+    command
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut bytes).ok();
+        }
+
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// scripts end their output with a line break, typing it would press Enter
+fn trim_line_break(mut output: String) -> String {
+    while output.ends_with('\n') || output.ends_with('\r') {
+        output.pop();
+    }
+
+    output
+}
+
+fn status_text(status: ExitStatus) -> String {
+    status.code().map(|code| code.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Shell, Variables, trim_line_break};
+
+    #[test]
+    fn only_trailing_line_breaks_are_trimmed() {
+        assert_eq!(trim_line_break("AB-123\r\n\n".into()), "AB-123");
+        assert_eq!(trim_line_break("a\nb\n".into()), "a\nb");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn output_is_kept_in_variables() {
+        let shell = Shell {
+            command: "echo AB-123; echo oops >&2; exit 4".into(),
+            stdout_variable: "ticket".into(),
+            stderr_variable: "errors".into(),
+            status_variable: "status".into(),
+            timeout_seconds: 10
+        };
+        let mut variables = Variables::default();
+
+        assert!(shell.run(&mut variables).unwrap());
+        assert_eq!(variables.get("ticket"), Some("AB-123"));
+        assert_eq!(variables.get("errors"), Some("oops"));
+        assert_eq!(variables.get("status"), Some("4"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn slow_command_times_out() {
+        let shell = Shell { command: "sleep 5".into(), timeout_seconds: 0, ..Default::default() };
+        let mut variables = Variables::default();
+
+        assert!(!shell.run(&mut variables).unwrap());
+        assert_eq!(variables.get("output"), None);
+    }
+}
@@ -0,0 +1,122 @@
+use image::{RgbaImage, GenericImageView};
+
+use super::image_search::is_opaque;
+
+/// the part of the image inside the rectangle, the rectangle is clipped to the image
+pub fn crop(image: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> Option<RgbaImage> {
+    let x = x.min(image.width());
+    let y = y.min(image.height());
+    let width = width.min(image.width() - x);
+    let height = height.min(image.height() - y);
+
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    Some(image.view(x, y, width, height).to_image())
+}
+
+/// cuts off rows and columns on the edges that are a single color (or fully masked),
+/// e.g. the background around a snipped button, at least one pixel is always left
+pub fn auto_trim(image: &RgbaImage) -> RgbaImage {
+    let (mut left, mut top) = (0, 0);
+    let (mut right, mut bottom) = (image.width(), image.height());
+
+    if right == 0 || bottom == 0 {
+        return image.clone();
+    }
+
+    while bottom - top > 1 && line_is_uniform(image, (left..right).map(|x| (x, top))) {
+        top += 1;
+    }
+
+    while bottom - top > 1 && line_is_uniform(image, (left..right).map(|x| (x, bottom - 1))) {
+        bottom -= 1;
+    }
+
+    while right - left > 1 && line_is_uniform(image, (top..bottom).map(|y| (left, y))) {
+        left += 1;
+    }
+
+    while right - left > 1 && line_is_uniform(image, (top..bottom).map(|y| (right - 1, y))) {
+        right -= 1;
+    }
+
+    image.view(left, top, right - left, bottom - top).to_image()
+}
+
+/// masked pixels are made transparent, so the search ignores them, unmasking makes them opaque again
+pub fn set_mask(image: &mut RgbaImage, x: i64, y: i64, width: u32, height: u32, masked: bool) {
+    let alpha = if masked { 0 } else { 255 };
+
+    for py in y.max(0)..(y + height as i64).min(image.height() as i64) {
+        for px in x.max(0)..(x + width as i64).min(image.width() as i64) {
+            image.get_pixel_mut(px as u32, py as u32).0[3] = alpha;
+        }
+    }
+}
+
+fn line_is_uniform(image: &RgbaImage, mut positions: impl Iterator<Item = (u32, u32)>) -> bool {
+    let first = match positions.next() {
+        Some((x, y)) => image.get_pixel(x, y),
+        None => return true,
+    };
+
+    positions.all(|(x, y)| {
+        let pixel = image.get_pixel(x, y);
+        pixel == first || (!is_opaque(pixel.0[3]) && !is_opaque(first.0[3]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{RgbaImage, Rgba};
+
+    use super::{auto_trim, crop, set_mask};
+
+    #[test]
+    fn crop_is_clipped_to_the_image() {
+        let image = RgbaImage::from_fn(10, 10, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+
+        let cropped = crop(&image, 8, 7, 5, 5).unwrap();
+
+        assert_eq!((cropped.width(), cropped.height()), (2, 3));
+        assert_eq!(cropped.get_pixel(0, 0), &Rgba([8, 7, 0, 255]));
+        assert!(crop(&image, 10, 0, 5, 5).is_none());
+    }
+
+    #[test]
+    fn uniform_borders_are_trimmed() {
+        // gray background with a 3x2 pattern at (4, 5)
+        let mut image = RgbaImage::from_pixel(12, 10, Rgba([128, 128, 128, 255]));
+        for (x, y) in [(4, 5), (6, 5), (5, 6)] {
+            image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        }
+
+        let trimmed = auto_trim(&image);
+
+        assert_eq!((trimmed.width(), trimmed.height()), (3, 2));
+        assert_eq!(trimmed.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn trimming_a_single_color_leaves_one_pixel() {
+        let image = RgbaImage::from_pixel(5, 5, Rgba([1, 2, 3, 255]));
+
+        let trimmed = auto_trim(&image);
+
+        assert_eq!((trimmed.width(), trimmed.height()), (1, 1));
+    }
+
+    #[test]
+    fn mask_is_clipped_and_reversible() {
+        let mut image = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 255]));
+
+        set_mask(&mut image, -1, -1, 2, 2, true);
+        assert_eq!(image.get_pixel(0, 0).0[3], 0);
+        assert_eq!(image.get_pixel(1, 0).0[3], 255);
+
+        set_mask(&mut image, 0, 0, 1, 1, false);
+        assert_eq!(image.get_pixel(0, 0).0[3], 255);
+    }
+}
@@ -1,9 +1,10 @@
 use anyhow::{Result, anyhow};
-use autopilot::{key::KeyCode, mouse::ScrollDirection};
+use autopilot::mouse::{Button, ScrollDirection};
 
-use super::MacroStep;
+use super::{MacroStep, MatchMode, OffsetUnit, MonitorChoice, Key, Comparison, CheckFailure};
+use super::screen;
 
-pub trait EnumInterString     // would use ToString and FromStr but can't impl those for KeyCodes from autopilot
+pub trait EnumInterString     // would use ToString and FromStr but can't impl those for enums from autopilot
 where
     Self: Sized
 {
@@ -25,19 +26,51 @@ impl EnumInterString for MacroStep {
             "Type text".to_string(),
             "Press key".to_string(),
             "Scroll".to_string(),
-            "Wait".to_string()
+            "Wait".to_string(),
+            "Wait for image to disappear".to_string(),
+            "Wait for color".to_string(),
+            "Check color".to_string(),
+            "Move to".to_string(),
+            "Move by".to_string(),
+            "Click at".to_string(),
+            "Drag".to_string(),
+            "Key down".to_string(),
+            "Key up".to_string(),
+            "Hold key".to_string(),
+            "Run shell command".to_string(),
+            "Set clipboard text".to_string(),
+            "Set clipboard image".to_string(),
+            "Read clipboard".to_string(),
+            "Wait for clipboard change".to_string(),
+            "Check variable".to_string()
         ]
     }
 
     fn to_string(&self) -> String {
         match self {
             MacroStep::Launch(_) => "Launch program",
-            MacroStep::ClickImage(_, _, _) => "Click an image",
-            MacroStep::MoveToImage(_, _, _) => "Move to image",
+            MacroStep::ClickImage(..) => "Click an image",
+            MacroStep::MoveToImage(..) => "Move to image",
             MacroStep::TypeText(_, _) => "Type text",
             MacroStep::PressKey(_, _) => "Press key",
             MacroStep::Scroll(_, _) => "Scroll",
             MacroStep::WaitTime(_) => "Wait",
+            MacroStep::WaitImageGone(..) => "Wait for image to disappear",
+            MacroStep::WaitColor(_) => "Wait for color",
+            MacroStep::CheckColor(..) => "Check color",
+            MacroStep::MoveTo(..) => "Move to",
+            MacroStep::MoveBy(..) => "Move by",
+            MacroStep::ClickAt(..) => "Click at",
+            MacroStep::Drag(_) => "Drag",
+            MacroStep::KeyDown(_) => "Key down",
+            MacroStep::KeyUp(_) => "Key up",
+            MacroStep::HoldKey(..) => "Hold key",
+            MacroStep::RunShell(_) => "Run shell command",
+            MacroStep::SetClipboardText(_) => "Set clipboard text",
+            MacroStep::SetClipboardImage(_) => "Set clipboard image",
+            MacroStep::ReadClipboard(_) => "Read clipboard",
+            MacroStep::WaitClipboardChange => "Wait for clipboard change",
+            MacroStep::CheckVariable(_) => "Check variable",
         }.to_string()
     }
 
@@ -50,16 +83,32 @@ impl EnumInterString for MacroStep {
             "Press key" => MacroStep::default_press_key(),
             "Scroll" => MacroStep::default_scroll(),
             "Wait" => MacroStep::default_wait(),
+            "Wait for image to disappear" => MacroStep::default_wait_image_gone(),
+            "Wait for color" => MacroStep::default_wait_color(),
+            "Check color" => MacroStep::default_check_color(),
+            "Move to" => MacroStep::default_move_to(),
+            "Move by" => MacroStep::default_move_by(),
+            "Click at" => MacroStep::default_click_at(),
+            "Drag" => MacroStep::default_drag(),
+            "Key down" => MacroStep::default_key_down(),
+            "Key up" => MacroStep::default_key_up(),
+            "Hold key" => MacroStep::default_hold_key(),
+            "Run shell command" => MacroStep::default_run_shell(),
+            "Set clipboard text" => MacroStep::default_set_clipboard_text(),
+            "Set clipboard image" => MacroStep::default_set_clipboard_image(),
+            "Read clipboard" => MacroStep::default_read_clipboard(),
+            "Wait for clipboard change" => MacroStep::default_wait_clipboard_change(),
+            "Check variable" => MacroStep::default_check_variable(),
             _ => return Err(anyhow!("Failed to convert string to MacroStep enum"))
         })
     }
 }
 
-impl EnumInterString for KeyCode {
+impl EnumInterString for Key {
     type Err = anyhow::Error;
 
     fn all_string_options() -> Vec<String> {
-        vec![
+        let mut options: Vec<String> = vec![
             "Left".to_string(),
             "Right".to_string(),
             "Down".to_string(),
@@ -103,103 +152,202 @@ impl EnumInterString for KeyCode {
             "F23".to_string(),
             "F24".to_string(),
             "Meta".to_string(),
-        ]
+            "Insert".to_string(),
+            "Print Screen".to_string(),
+            "Scroll Lock".to_string(),
+            "Pause".to_string(),
+            "Num Lock".to_string(),
+            "Menu".to_string(),
+            "Numpad 0".to_string(),
+            "Numpad 1".to_string(),
+            "Numpad 2".to_string(),
+            "Numpad 3".to_string(),
+            "Numpad 4".to_string(),
+            "Numpad 5".to_string(),
+            "Numpad 6".to_string(),
+            "Numpad 7".to_string(),
+            "Numpad 8".to_string(),
+            "Numpad 9".to_string(),
+            "Numpad +".to_string(),
+            "Numpad -".to_string(),
+            "Numpad *".to_string(),
+            "Numpad /".to_string(),
+            "Numpad .".to_string(),
+            "Numpad Enter".to_string(),
+            "Volume Up".to_string(),
+            "Volume Down".to_string(),
+            "Mute".to_string(),
+            "Play/Pause".to_string(),
+            "Next Track".to_string(),
+            "Previous Track".to_string(),
+            "Stop Media".to_string()
+        ];
+
+        options.extend(Key::CHARACTERS.chars().map(String::from));
+
+        options
     }
 
     fn to_string(&self) -> String {
         match self {
-            KeyCode::F1 => "F1",
-            KeyCode::F2 => "F2",
-            KeyCode::F3 => "F3",
-            KeyCode::F4 => "F4",
-            KeyCode::F5 => "F5",
-            KeyCode::F6 => "F6",
-            KeyCode::F7 => "F7",
-            KeyCode::F8 => "F8",
-            KeyCode::F9 => "F9",
-            KeyCode::F10 => "F10",
-            KeyCode::F11 => "F11",
-            KeyCode::F12 => "F12",
-            KeyCode::F13 => "F13",
-            KeyCode::F14 => "F14",
-            KeyCode::F15 => "F15",
-            KeyCode::F16 => "F16",
-            KeyCode::F17 => "F17",
-            KeyCode::F18 => "F18",
-            KeyCode::F19 => "F19",
-            KeyCode::F20 => "F20",
-            KeyCode::F21 => "F21",
-            KeyCode::F22 => "F22",
-            KeyCode::F23 => "F23",
-            KeyCode::F24 => "F24",
-            KeyCode::LeftArrow => "Left",
-            KeyCode::Control => "Ctrl",
-            KeyCode::RightArrow => "Right",
-            KeyCode::DownArrow => "Down",
-            KeyCode::End => "End",
-            KeyCode::UpArrow => "Up",
-            KeyCode::PageUp => "Page Up",
-            KeyCode::Alt => "Alt",
-            KeyCode::Return => "Enter",
-            KeyCode::PageDown => "Page Down",
-            KeyCode::Delete => "Del",
-            KeyCode::Home => "Home",
-            KeyCode::Escape => "Esc",
-            KeyCode::Backspace => "Backspace",
-            KeyCode::Meta => "Meta",
-            KeyCode::CapsLock => "Caps Lock",
-            KeyCode::Shift => "Shift",
-            KeyCode::Tab => "Tab",
-            KeyCode::Space => "Space",
+            Key::F1 => "F1",
+            Key::F2 => "F2",
+            Key::F3 => "F3",
+            Key::F4 => "F4",
+            Key::F5 => "F5",
+            Key::F6 => "F6",
+            Key::F7 => "F7",
+            Key::F8 => "F8",
+            Key::F9 => "F9",
+            Key::F10 => "F10",
+            Key::F11 => "F11",
+            Key::F12 => "F12",
+            Key::F13 => "F13",
+            Key::F14 => "F14",
+            Key::F15 => "F15",
+            Key::F16 => "F16",
+            Key::F17 => "F17",
+            Key::F18 => "F18",
+            Key::F19 => "F19",
+            Key::F20 => "F20",
+            Key::F21 => "F21",
+            Key::F22 => "F22",
+            Key::F23 => "F23",
+            Key::F24 => "F24",
+            Key::LeftArrow => "Left",
+            Key::Control => "Ctrl",
+            Key::RightArrow => "Right",
+            Key::DownArrow => "Down",
+            Key::End => "End",
+            Key::UpArrow => "Up",
+            Key::PageUp => "Page Up",
+            Key::Alt => "Alt",
+            Key::Return => "Enter",
+            Key::PageDown => "Page Down",
+            Key::Delete => "Del",
+            Key::Home => "Home",
+            Key::Escape => "Esc",
+            Key::Backspace => "Backspace",
+            Key::Meta => "Meta",
+            Key::CapsLock => "Caps Lock",
+            Key::Shift => "Shift",
+            Key::Tab => "Tab",
+            Key::Space => "Space",
+            Key::Insert => "Insert",
+            Key::PrintScreen => "Print Screen",
+            Key::ScrollLock => "Scroll Lock",
+            Key::Pause => "Pause",
+            Key::NumLock => "Num Lock",
+            Key::Menu => "Menu",
+            Key::Numpad0 => "Numpad 0",
+            Key::Numpad1 => "Numpad 1",
+            Key::Numpad2 => "Numpad 2",
+            Key::Numpad3 => "Numpad 3",
+            Key::Numpad4 => "Numpad 4",
+            Key::Numpad5 => "Numpad 5",
+            Key::Numpad6 => "Numpad 6",
+            Key::Numpad7 => "Numpad 7",
+            Key::Numpad8 => "Numpad 8",
+            Key::Numpad9 => "Numpad 9",
+            Key::NumpadAdd => "Numpad +",
+            Key::NumpadSubtract => "Numpad -",
+            Key::NumpadMultiply => "Numpad *",
+            Key::NumpadDivide => "Numpad /",
+            Key::NumpadDecimal => "Numpad .",
+            Key::NumpadEnter => "Numpad Enter",
+            Key::VolumeUp => "Volume Up",
+            Key::VolumeDown => "Volume Down",
+            Key::VolumeMute => "Mute",
+            Key::MediaPlayPause => "Play/Pause",
+            Key::MediaNext => "Next Track",
+            Key::MediaPrevious => "Previous Track",
+            Key::MediaStop => "Stop Media",
+            Key::Character(character) => return character.to_string(),
         }.to_string()
     }
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
-            "F1" => KeyCode::F1,
-            "F2" => KeyCode::F2,
-            "F3" => KeyCode::F3,
-            "F4" => KeyCode::F4,
-            "F5" => KeyCode::F5,
-            "F6" => KeyCode::F6,
-            "F7" => KeyCode::F7,
-            "F8" => KeyCode::F8,
-            "F9" => KeyCode::F9,
-            "F10" => KeyCode::F10,
-            "F11" => KeyCode::F11,
-            "F12" => KeyCode::F12,
-            "F13" => KeyCode::F13,
-            "F14" => KeyCode::F14,
-            "F15" => KeyCode::F15,
-            "F16" => KeyCode::F16,
-            "F17" => KeyCode::F17,
-            "F18" => KeyCode::F18,
-            "F19" => KeyCode::F19,
-            "F20" => KeyCode::F20,
-            "F21" => KeyCode::F21,
-            "F22" => KeyCode::F22,
-            "F23" => KeyCode::F23,
-            "F24" => KeyCode::F24,
-            "Left" => KeyCode::LeftArrow,
-            "Ctrl" => KeyCode::Control,
-            "Right" => KeyCode::RightArrow,
-            "Down" => KeyCode::DownArrow,
-            "End" => KeyCode::End,
-            "Up" => KeyCode::UpArrow,
-            "Page Up" => KeyCode::PageUp,
-            "Alt" => KeyCode::Alt,
-            "Enter" => KeyCode::Return,
-            "Page Down" => KeyCode::PageDown,
-            "Del" => KeyCode::Delete,
-            "Home" => KeyCode::Home,
-            "Esc" => KeyCode::Escape,
-            "Backspace" => KeyCode::Backspace,
-            "Meta" => KeyCode::Meta,
-            "Caps Lock" => KeyCode::CapsLock,
-            "Shift" => KeyCode::Shift,
-            "Tab" => KeyCode::Tab,
-            "Space" => KeyCode::Space,
-            _ => return Err(anyhow!("Failed to convert string to KeyCode enum"))
+            "F1" => Key::F1,
+            "F2" => Key::F2,
+            "F3" => Key::F3,
+            "F4" => Key::F4,
+            "F5" => Key::F5,
+            "F6" => Key::F6,
+            "F7" => Key::F7,
+            "F8" => Key::F8,
+            "F9" => Key::F9,
+            "F10" => Key::F10,
+            "F11" => Key::F11,
+            "F12" => Key::F12,
+            "F13" => Key::F13,
+            "F14" => Key::F14,
+            "F15" => Key::F15,
+            "F16" => Key::F16,
+            "F17" => Key::F17,
+            "F18" => Key::F18,
+            "F19" => Key::F19,
+            "F20" => Key::F20,
+            "F21" => Key::F21,
+            "F22" => Key::F22,
+            "F23" => Key::F23,
+            "F24" => Key::F24,
+            "Left" => Key::LeftArrow,
+            "Ctrl" => Key::Control,
+            "Right" => Key::RightArrow,
+            "Down" => Key::DownArrow,
+            "End" => Key::End,
+            "Up" => Key::UpArrow,
+            "Page Up" => Key::PageUp,
+            "Alt" => Key::Alt,
+            "Enter" => Key::Return,
+            "Page Down" => Key::PageDown,
+            "Del" => Key::Delete,
+            "Home" => Key::Home,
+            "Esc" => Key::Escape,
+            "Backspace" => Key::Backspace,
+            "Meta" => Key::Meta,
+            "Caps Lock" => Key::CapsLock,
+            "Shift" => Key::Shift,
+            "Tab" => Key::Tab,
+            "Space" => Key::Space,
+            "Insert" => Key::Insert,
+            "Print Screen" => Key::PrintScreen,
+            "Scroll Lock" => Key::ScrollLock,
+            "Pause" => Key::Pause,
+            "Num Lock" => Key::NumLock,
+            "Menu" => Key::Menu,
+            "Numpad 0" => Key::Numpad0,
+            "Numpad 1" => Key::Numpad1,
+            "Numpad 2" => Key::Numpad2,
+            "Numpad 3" => Key::Numpad3,
+            "Numpad 4" => Key::Numpad4,
+            "Numpad 5" => Key::Numpad5,
+            "Numpad 6" => Key::Numpad6,
+            "Numpad 7" => Key::Numpad7,
+            "Numpad 8" => Key::Numpad8,
+            "Numpad 9" => Key::Numpad9,
+            "Numpad +" => Key::NumpadAdd,
+            "Numpad -" => Key::NumpadSubtract,
+            "Numpad *" => Key::NumpadMultiply,
+            "Numpad /" => Key::NumpadDivide,
+            "Numpad ." => Key::NumpadDecimal,
+            "Numpad Enter" => Key::NumpadEnter,
+            "Volume Up" => Key::VolumeUp,
+            "Volume Down" => Key::VolumeDown,
+            "Mute" => Key::VolumeMute,
+            "Play/Pause" => Key::MediaPlayPause,
+            "Next Track" => Key::MediaNext,
+            "Previous Track" => Key::MediaPrevious,
+            "Stop Media" => Key::MediaStop,
+            _ => {
+                let mut chars = s.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(character), None) if !character.is_whitespace() => Key::Character(character.to_lowercase().next().unwrap_or(character)),
+                    _ => return Err(anyhow!("Failed to convert string to Key enum")),
+                }
+            }
         })
     }
 }
@@ -230,15 +378,204 @@ impl EnumInterString for ScrollDirection {
     }
 }
 
+impl EnumInterString for Button {
+    type Err = anyhow::Error;
+
+    fn all_string_options() -> Vec<String> {
+        vec![
+            "Left".to_string(),
+            "Right".to_string(),
+            "Middle".to_string()
+        ]
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            Button::Left => "Left",
+            Button::Right => "Right",
+            Button::Middle => "Middle",
+        }.to_string()
+    }
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Left" => Button::Left,
+            "Right" => Button::Right,
+            "Middle" => Button::Middle,
+            _ => return Err(anyhow!("Failed to convert string to Button enum"))
+        })
+    }
+}
+
+impl EnumInterString for MatchMode {
+    type Err = anyhow::Error;
+
+    fn all_string_options() -> Vec<String> {
+        vec![
+            "Exact".to_string(),
+            "Grayscale".to_string(),
+            "Ignore color".to_string(),
+            "Edges".to_string()
+        ]
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            MatchMode::Exact => "Exact",
+            MatchMode::Grayscale => "Grayscale",
+            MatchMode::ColorInsensitive => "Ignore color",
+            MatchMode::Edges => "Edges",
+        }.to_string()
+    }
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Exact" => MatchMode::Exact,
+            "Grayscale" => MatchMode::Grayscale,
+            "Ignore color" => MatchMode::ColorInsensitive,
+            "Edges" => MatchMode::Edges,
+            _ => return Err(anyhow!("Failed to convert string to MatchMode enum"))
+        })
+    }
+}
+
+impl EnumInterString for OffsetUnit {
+    type Err = anyhow::Error;
+
+    fn all_string_options() -> Vec<String> {
+        vec![
+            "px".to_string(),
+            "% of size".to_string()
+        ]
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            OffsetUnit::Pixels => "px",
+            OffsetUnit::Fraction => "% of size",
+        }.to_string()
+    }
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "px" => OffsetUnit::Pixels,
+            "% of size" => OffsetUnit::Fraction,
+            _ => return Err(anyhow!("Failed to convert string to OffsetUnit enum"))
+        })
+    }
+}
+
+impl EnumInterString for Comparison {
+    type Err = anyhow::Error;
+
+    fn all_string_options() -> Vec<String> {
+        vec![
+            "is".to_string(),
+            "is not".to_string(),
+            "contains".to_string(),
+            "matches regex".to_string()
+        ]
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            Comparison::Equals => "is",
+            Comparison::NotEquals => "is not",
+            Comparison::Contains => "contains",
+            Comparison::Matches => "matches regex",
+        }.to_string()
+    }
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "is" => Comparison::Equals,
+            "is not" => Comparison::NotEquals,
+            "contains" => Comparison::Contains,
+            "matches regex" => Comparison::Matches,
+            _ => return Err(anyhow!("Failed to convert string to Comparison enum"))
+        })
+    }
+}
+
+impl EnumInterString for CheckFailure {
+    type Err = anyhow::Error;
+
+    fn all_string_options() -> Vec<String> {
+        vec![
+            "Skip steps".to_string(),
+            "Stop the macro".to_string(),
+            "Fail the macro".to_string()
+        ]
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            CheckFailure::SkipNext(_) => "Skip steps",
+            CheckFailure::Stop => "Stop the macro",
+            CheckFailure::Fail => "Fail the macro",
+        }.to_string()
+    }
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Skip steps" => CheckFailure::SkipNext(1),
+            "Stop the macro" => CheckFailure::Stop,
+            "Fail the macro" => CheckFailure::Fail,
+            _ => return Err(anyhow!("Failed to convert string to CheckFailure enum"))
+        })
+    }
+}
+
+impl EnumInterString for MonitorChoice {
+    type Err = anyhow::Error;
+
+    /// the numbered options depend on how many monitors are connected right now
+    fn all_string_options() -> Vec<String> {
+        let mut options = vec![
+            "Primary monitor".to_string(),
+            "All monitors".to_string(),
+            "Monitor under cursor".to_string()
+        ];
+
+        options.extend((0..screen::monitor_count()).map(|index| MonitorChoice::Index(index).to_string()));
+
+        options
+    }
+
+    fn to_string(&self) -> String {
+        match self {
+            MonitorChoice::Primary => "Primary monitor".to_string(),
+            MonitorChoice::Index(index) => format!("Monitor {}", index + 1),
+            MonitorChoice::All => "All monitors".to_string(),
+            MonitorChoice::UnderCursor => "Monitor under cursor".to_string(),
+        }
+    }
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Primary monitor" => MonitorChoice::Primary,
+            "All monitors" => MonitorChoice::All,
+            "Monitor under cursor" => MonitorChoice::UnderCursor,
+            _ => {
+                let number: usize = s.strip_prefix("Monitor ")
+                    .and_then(|number| number.parse().ok())
+                    .filter(|number| *number > 0)
+                    .ok_or(anyhow!("Failed to convert string to MonitorChoice enum"))?;
+
+                MonitorChoice::Index(number - 1)
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // tests module to make sure that there are no typos n stuff in EnumInterString implementations
     // it doesn't check whether all_string_options is exaustive, because i'm not sure if that's possible
 
-    use autopilot::key::KeyCode;
-    use autopilot::mouse::ScrollDirection;
+    use autopilot::mouse::{Button, ScrollDirection};
 
-    use super::MacroStep;
+    use super::{MacroStep, MatchMode, OffsetUnit, MonitorChoice, Key, Comparison, CheckFailure};
     use super::EnumInterString;
 
     #[test]
@@ -265,21 +602,21 @@ mod tests {
     }
 
     #[test]
-    fn check_keycode_from_str() {
-        let options = KeyCode::all_string_options();
+    fn check_key_from_str() {
+        let options = Key::all_string_options();
 
         for option in options {
-            let a = KeyCode::from_str(&option);
+            let a = Key::from_str(&option);
             assert!(a.is_ok());
         }
     }
 
     #[test]
-    fn check_keycode_to_string() {
-        let options = KeyCode::all_string_options();
+    fn check_key_to_string() {
+        let options = Key::all_string_options();
 
         for option in options {
-            let a = KeyCode::from_str(&option).unwrap();
+            let a = Key::from_str(&option).unwrap();
             
             let res = a.to_string();
 
@@ -308,4 +645,120 @@ mod tests {
             assert!(res == option);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn check_button_from_str() {
+        let options = Button::all_string_options();
+
+        for option in options {
+            let a = Button::from_str(&option);
+            assert!(a.is_ok());
+        }
+    }
+
+    #[test]
+    fn check_button_to_string() {
+        let options = Button::all_string_options();
+
+        for option in options {
+            let a = Button::from_str(&option).unwrap();
+            
+            let res = a.to_string();
+
+            assert!(res == option);
+        }
+    }
+
+    #[test]
+    fn check_match_mode_from_str() {
+        let options = MatchMode::all_string_options();
+
+        for option in options {
+            let a = MatchMode::from_str(&option);
+            assert!(a.is_ok());
+        }
+    }
+
+    #[test]
+    fn check_match_mode_to_string() {
+        let options = MatchMode::all_string_options();
+
+        for option in options {
+            let a = MatchMode::from_str(&option).unwrap();
+            
+            let res = a.to_string();
+
+            assert!(res == option);
+        }
+    }
+    #[test]
+    fn check_offset_unit_from_str() {
+        let options = OffsetUnit::all_string_options();
+
+        for option in options {
+            let a = OffsetUnit::from_str(&option);
+            assert!(a.is_ok());
+        }
+    }
+
+    #[test]
+    fn check_offset_unit_to_string() {
+        let options = OffsetUnit::all_string_options();
+
+        for option in options {
+            let a = OffsetUnit::from_str(&option).unwrap();
+            
+            let res = a.to_string();
+
+            assert!(res == option);
+        }
+    }
+
+    #[test]
+    fn check_comparison_to_string() {
+        let options = Comparison::all_string_options();
+
+        for option in options {
+            let a = Comparison::from_str(&option).unwrap();
+            
+            let res = a.to_string();
+
+            assert!(res == option);
+        }
+    }
+
+    #[test]
+    fn check_check_failure_to_string() {
+        let options = CheckFailure::all_string_options();
+
+        for option in options {
+            let a = CheckFailure::from_str(&option).unwrap();
+            
+            let res = a.to_string();
+
+            assert!(res == option);
+        }
+    }
+
+    #[test]
+    fn check_monitor_choice_round_trip() {
+        // the numbered options depend on the monitors of the machine, so a few are checked directly
+        let choices = [MonitorChoice::Primary, MonitorChoice::All, MonitorChoice::UnderCursor, MonitorChoice::Index(0), MonitorChoice::Index(2)];
+
+        for choice in choices {
+            assert!(MonitorChoice::from_str(&choice.to_string()).unwrap() == choice);
+        }
+
+        assert!(MonitorChoice::from_str("Monitor 0").is_err());
+    }
+
+    #[test]
+    fn check_key_characters() {
+        // any single character is a key, not only the ones offered
+        assert!(Key::from_str("T").unwrap() == Key::Character('t'));
+        assert!(Key::from_str("ä").unwrap() == Key::Character('ä'));
+        assert!(Key::from_str("Numpad 5").unwrap() == Key::Numpad5);
+        assert!(Key::from_str("tt").is_err());
+        assert!(Key::from_str(" ").is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::{Result, anyhow};
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+
+use super::variables::Variables;
+
+/// compares a variable an earlier step stored, e.g. what a shell command printed or its exit code
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VariableCheck {
+    pub name: String,               // a variable that isn't set compares as empty text
+    pub comparison: Comparison,
+    pub value: String,              // variables in it are replaced, a regular expression for Matches
+    pub on_fail: CheckFailure
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Equals,
+    NotEquals,
+    Contains,
+    Matches
+}
+
+/// what the macro does when the variable doesn't compare like the step says
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckFailure {
+    SkipNext(u32),      // this many of the following steps are skipped
+    Stop,               // the macro ends without an error
+    Fail                // the macro ends with an error that says what the variable was
+}
+
+impl Default for VariableCheck {
+    fn default() -> Self {
+        // a shell command that exited with 0 succeeded
+        Self {
+            name: "status".into(),
+            comparison: Comparison::Equals,
+            value: "0".into(),
+            on_fail: CheckFailure::Fail
+        }
+    }
+}
+
+impl VariableCheck {
+    pub fn passes(&self, variables: &Variables) -> Result<bool> {
+        let actual = variables.get(&self.name).unwrap_or_default();
+        let expected = variables.expand(&self.value);
+
+        Ok(match self.comparison {
+            Comparison::Equals => actual == expected,
+            Comparison::NotEquals => actual != expected,
+            Comparison::Contains => actual.contains(&expected),
+            Comparison::Matches => Regex::new(&expected)
+                .map_err(|err| anyhow!("Invalid regular expression: {}", err))?
+                .is_match(actual),
+        })
+    }
+
+    /// the error the macro ends with when the check fails and the step says to fail it
+    pub fn failure_message(&self, variables: &Variables) -> String {
+        let comparison = match self.comparison {
+            Comparison::Equals => "be",
+            Comparison::NotEquals => "not be",
+            Comparison::Contains => "contain",
+            Comparison::Matches => "match",
+        };
+
+        format!(
+            "The variable {} is \"{}\", the check expected it to {} \"{}\"",
+            self.name,
+            variables.get(&self.name).unwrap_or_default(),
+            comparison,
+            variables.expand(&self.value)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VariableCheck, Comparison, CheckFailure, Variables};
+
+    fn check(comparison: Comparison, value: &str) -> VariableCheck {
+        VariableCheck { name: "output".into(), comparison, value: value.into(), on_fail: CheckFailure::Fail }
+    }
+
+    fn variables() -> Variables {
+        let mut variables = Variables::default();
+
+        variables.set("output", "Created AB-123".into());
+        variables.set("project", "AB".into());
+        variables.set("status", "0".into());
+
+        variables
+    }
+
+    #[test]
+    fn comparisons() {
+        let variables = variables();
+
+        assert!(check(Comparison::Equals, "Created AB-123").passes(&variables).unwrap());
+        assert!(!check(Comparison::Equals, "created ab-123").passes(&variables).unwrap());
+        assert!(check(Comparison::NotEquals, "").passes(&variables).unwrap());
+        assert!(check(Comparison::Contains, "${project}-").passes(&variables).unwrap());
+        assert!(check(Comparison::Matches, r"^Created [A-Z]+-\d+$").passes(&variables).unwrap());
+        assert!(!check(Comparison::Matches, r"^\d+$").passes(&variables).unwrap());
+    }
+
+    #[test]
+    fn exit_code_of_a_shell_command() {
+        let variables = variables();
+
+        assert!(VariableCheck::default().passes(&variables).unwrap());
+        assert!(!VariableCheck { value: "1".into(), ..Default::default() }.passes(&variables).unwrap());
+    }
+
+    #[test]
+    fn unset_variable_is_empty() {
+        let variables = Variables::default();
+
+        assert!(check(Comparison::Equals, "").passes(&variables).unwrap());
+        assert!(!check(Comparison::Contains, "AB").passes(&variables).unwrap());
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        assert!(check(Comparison::Matches, "(AB").passes(&variables()).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use super::clipboard::ClipboardContent;
+
+/// values that steps store while the macro runs, other steps use them by writing ${name}
+#[derive(Default, Debug)]
+pub struct Variables {
+    values: HashMap<String, String>,
+    pub clipboard: Option<ClipboardContent>     // as the last clipboard step saw it, a change is anything else
+}
+
+impl Variables {
+    /// remembers what the clipboard holds when the macro starts, so waiting for a change notices a copy
+    /// made by the steps before the waiting one
+    pub fn new() -> Self {
+        Self { values: HashMap::new(), clipboard: ClipboardContent::read() }
+    }
+
+    /// a step without a name for one of its values doesn't keep it
+    pub fn set(&mut self, name: &str, value: String) {
+        if !name.is_empty() {
+            self.values.insert(name.to_string(), value);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// the text with every ${name} of a set variable replaced by its value, the others are kept as they are
+    pub fn expand(&self, text: &str) -> String {
+        let mut res = String::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find("${") {
+            res.push_str(&rest[..start]);
+
+            let after = &rest[start + 2..];
+            let value = after.find('}').and_then(|end| Some((end, self.get(&after[..end])?)));
+
+            match value {
+                Some((end, value)) => {
+                    res.push_str(value);
+                    rest = &after[end + 1..];
+                },
+                None => {
+                    res.push_str("${");
+                    rest = after;
+                },
+            }
+        }
+
+        res.push_str(rest);
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Variables;
+
+    #[test]
+    fn expand_set_variables() {
+        let mut variables = Variables::default();
+
+        variables.set("ticket", "AB-123".into());
+        variables.set("status", "0".into());
+
+        assert_eq!(variables.expand("Fixes ${ticket} (${status})"), "Fixes AB-123 (0)");
+        assert_eq!(variables.expand("${ticket}${ticket}"), "AB-123AB-123");
+    }
+
+    #[test]
+    fn keep_unknown_variables() {
+        let mut variables = Variables::default();
+
+        variables.set("ticket", "AB-123".into());
+
+        assert_eq!(variables.expand("${other} ${ticket} ${ticket"), "${other} AB-123 ${ticket");
+        assert_eq!(variables.expand("$ {} ${${ticket}"), "$ {} ${AB-123");
+    }
+
+    #[test]
+    fn unnamed_values_are_dropped() {
+        let mut variables = Variables::default();
+
+        variables.set("", "output".into());
+
+        assert_eq!(variables.get(""), None);
+        assert_eq!(variables.expand("${}"), "${}");
+    }
+}
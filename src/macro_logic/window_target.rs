@@ -0,0 +1,258 @@
+use anyhow::{Result, anyhow};
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+
+use super::screen::Bounds;
+
+/// the window a macro works in, found by regular expressions on its title and class,
+/// an empty expression matches any window
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowTarget {
+    pub title: String,
+    pub class: String       // WM_CLASS on X11, the name of the executable elsewhere
+}
+
+/// a visible window as the system reports it
+#[derive(Clone, Debug)]
+pub struct WindowInfo {
+    pub title: String,
+    pub class: String,
+    pub bounds: Bounds
+}
+
+impl WindowTarget {
+    /// where the window is on the virtual desktop right now, it's looked up again for every screenshot
+    /// so the macro follows the window when it's moved
+    pub fn locate(&self) -> Result<Bounds> {
+        let windows = list_windows()?;
+
+        Ok(self.pick(&windows)?.ok_or(anyhow!("No window matches the title \"{}\" and class \"{}\"", self.title, self.class))?.bounds)
+    }
+
+    /// the topmost matching window, `windows` are expected topmost first
+    pub fn pick<'a>(&self, windows: &'a [WindowInfo]) -> Result<Option<&'a WindowInfo>> {
+        let title = Regex::new(&self.title).map_err(|err| anyhow!("Invalid window title expression:\n{}", err))?;
+        let class = Regex::new(&self.class).map_err(|err| anyhow!("Invalid window class expression:\n{}", err))?;
+
+        Ok(windows.iter().find(|window| {
+            window.bounds.width > 0 && window.bounds.height > 0 && title.is_match(&window.title) && class.is_match(&window.class)
+        }))
+    }
+}
+
+/// walks the whole window tree instead of asking the window manager, so it also works without one (e.g. under Xvfb)
+#[cfg(target_os = "linux")]
+fn list_windows() -> Result<Vec<WindowInfo>> {
+    use std::ptr;
+    use x11::xlib;
+
+    unsafe {
+        let display = xlib::XOpenDisplay(ptr::null());
+
+        if display.is_null() {
+            return Err(anyhow!("Failed to open the X display"));
+        }
+
+        let root = xlib::XDefaultRootWindow(display);
+        let mut windows = Vec::new();
+
+        collect_windows(display, root, root, &mut windows);
+        xlib::XCloseDisplay(display);
+
+        Ok(windows)
+    }
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn collect_windows(display: *mut x11::xlib::Display, root: x11::xlib::Window, parent: x11::xlib::Window, windows: &mut Vec<WindowInfo>) {
+    use std::{mem, ptr, slice};
+    use std::os::raw::c_uint;
+    use x11::xlib;
+
+    let (mut root_return, mut parent_return) = (0, 0);
+    let mut children: *mut xlib::Window = ptr::null_mut();
+    let mut count: c_uint = 0;
+
+    if xlib::XQueryTree(display, parent, &mut root_return, &mut parent_return, &mut children, &mut count) == 0 || children.is_null() {
+        return;
+    }
+
+    let children_list = slice::from_raw_parts(children, count as usize).to_vec();
+    xlib::XFree(children as *mut _);
+
+    // children are listed bottom to top
+    for child in children_list.into_iter().rev() {
+        let mut attributes: xlib::XWindowAttributes = mem::zeroed();
+
+        // unmapped and minimized windows can't be searched in
+        if xlib::XGetWindowAttributes(display, child, &mut attributes) == 0 || attributes.map_state != xlib::IsViewable {
+            continue;
+        }
+
+        let title = window_title(display, child);
+        let class = window_class(display, child);
+
+        // window managers put decorations around the windows of applications, those only have a name on the inside
+        if !title.is_empty() || !class.is_empty() {
+            let (mut x, mut y, mut child_return) = (0, 0, 0);
+            xlib::XTranslateCoordinates(display, child, root, 0, 0, &mut x, &mut y, &mut child_return);
+
+            windows.push(WindowInfo {
+                title,
+                class,
+                bounds: Bounds { x, y, width: attributes.width as u32, height: attributes.height as u32 }
+            });
+        }
+
+        collect_windows(display, root, child, windows);
+    }
+}
+
+/// _NET_WM_NAME in UTF-8 if the window has it, WM_NAME otherwise
+#[cfg(target_os = "linux")]
+unsafe fn window_title(display: *mut x11::xlib::Display, window: x11::xlib::Window) -> String {
+    use std::ffi::CStr;
+    use std::{ptr, slice};
+    use x11::xlib;
+
+    let net_wm_name = xlib::XInternAtom(display, c"_NET_WM_NAME".as_ptr(), xlib::False);
+    let utf8_string = xlib::XInternAtom(display, c"UTF8_STRING".as_ptr(), xlib::False);
+
+    let (mut actual_type, mut actual_format, mut item_count, mut bytes_after) = (0, 0, 0, 0);
+    let mut value: *mut u8 = ptr::null_mut();
+
+    let status = xlib::XGetWindowProperty(
+        display, window, net_wm_name, 0, 1024, xlib::False, utf8_string,
+        &mut actual_type, &mut actual_format, &mut item_count, &mut bytes_after, &mut value
+    );
+
+    if status == xlib::Success as i32 && !value.is_null() {
+        let title = String::from_utf8_lossy(slice::from_raw_parts(value, item_count as usize)).into_owned();
+        xlib::XFree(value as *mut _);
+
+        if !title.is_empty() {
+            return title;
+        }
+    }
+
+    let mut name = ptr::null_mut();
+
+    if xlib::XFetchName(display, window, &mut name) == 0 || name.is_null() {
+        return String::new();
+    }
+
+    let title = CStr::from_ptr(name).to_string_lossy().into_owned();
+    xlib::XFree(name as *mut _);
+
+    title
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn window_class(display: *mut x11::xlib::Display, window: x11::xlib::Window) -> String {
+    use std::ffi::CStr;
+    use std::ptr;
+    use x11::xlib;
+
+    let mut hint = xlib::XClassHint { res_name: ptr::null_mut(), res_class: ptr::null_mut() };
+
+    if xlib::XGetClassHint(display, window, &mut hint) == 0 {
+        return String::new();
+    }
+
+    let class = match hint.res_class.is_null() {
+        true => String::new(),
+        false => CStr::from_ptr(hint.res_class).to_string_lossy().into_owned(),
+    };
+
+    for part in [hint.res_name, hint.res_class] {
+        if !part.is_null() {
+            xlib::XFree(part as *mut _);
+        }
+    }
+
+    class
+}
+
+#[cfg(not(target_os = "linux"))]
+fn list_windows() -> Result<Vec<WindowInfo>> {
+    Ok(xcap::Window::all()?
+        .iter()
+        .filter(|window| !window.is_minimized())
+        .map(|window| WindowInfo {
+            title: window.title().to_string(),
+            class: window.app_name().to_string(),
+            bounds: Bounds { x: window.x(), y: window.y(), width: window.width(), height: window.height() }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WindowTarget, WindowInfo};
+    use crate::macro_logic::screen::Bounds;
+
+    fn window(title: &str, class: &str, x: i32) -> WindowInfo {
+        WindowInfo { title: title.to_string(), class: class.to_string(), bounds: Bounds { x, y: 0, width: 100, height: 100 } }
+    }
+
+    #[test]
+    fn topmost_match_is_picked() {
+        let windows = [
+            window("notes.txt - Editor", "editor", 0),
+            window("Project X - Browser", "browser", 100),
+            window("Project Y - Browser", "browser", 200)
+        ];
+
+        let target = WindowTarget { title: "^Project .* - Browser$".to_string(), class: String::new() };
+        assert_eq!(target.pick(&windows).unwrap().unwrap().bounds.x, 100);
+
+        let target = WindowTarget { title: "Project Y".to_string(), class: "^browser$".to_string() };
+        assert_eq!(target.pick(&windows).unwrap().unwrap().bounds.x, 200);
+
+        let target = WindowTarget { title: "Project".to_string(), class: "editor".to_string() };
+        assert!(target.pick(&windows).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_expression_is_an_error() {
+        let target = WindowTarget { title: "(unclosed".to_string(), class: String::new() };
+
+        assert!(target.pick(&[]).is_err());
+    }
+
+    /// needs an X server without other windows called like this one, e.g. `xvfb-run cargo test -- --ignored`
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore]
+    fn window_is_found_on_x11() {
+        use std::ffi::CString;
+        use std::ptr;
+        use x11::xlib;
+
+        unsafe {
+            let display = xlib::XOpenDisplay(ptr::null());
+            assert!(!display.is_null(), "no X display to test with");
+
+            let root = xlib::XDefaultRootWindow(display);
+            let window = xlib::XCreateSimpleWindow(display, root, 30, 40, 200, 100, 0, 0, 0);
+
+            let title = CString::new("screen macro window test").unwrap();
+            let (name, class) = (CString::new("screen-macro").unwrap(), CString::new("ScreenMacroTest").unwrap());
+            let mut hint = xlib::XClassHint { res_name: name.as_ptr() as *mut _, res_class: class.as_ptr() as *mut _ };
+
+            xlib::XStoreName(display, window, title.as_ptr());
+            xlib::XSetClassHint(display, window, &mut hint);
+            xlib::XMapWindow(display, window);
+            xlib::XSync(display, xlib::False);
+
+            let target = WindowTarget { title: "window test$".to_string(), class: "^ScreenMacro".to_string() };
+            let bounds = target.locate();
+
+            xlib::XDestroyWindow(display, window);
+            xlib::XCloseDisplay(display);
+
+            // without a window manager the window stays exactly where it was created
+            assert_eq!(bounds.unwrap(), Bounds { x: 30, y: 40, width: 200, height: 100 });
+        }
+    }
+}
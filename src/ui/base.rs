@@ -1,5 +1,5 @@
 use iced::pure::{Application, container, text, Element};
-use iced::{Length, executor, Command};
+use iced::{Length, executor, Command, Subscription, window};
 use iced_aw::pure::{Card, Modal};
 use iced_pure::button;
 use rfd::FileDialog;
@@ -47,6 +47,20 @@ impl Application for Base {
         "ScreenMacro".into()
     }
 
+    fn mode(&self) -> window::Mode {
+        match &self.selected {
+            WindowShowing::Start => window::Mode::Windowed,
+            WindowShowing::MacroMenu => self.macro_menu.window_mode(),
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        match &self.selected {
+            WindowShowing::Start => Subscription::none(),
+            WindowShowing::MacroMenu => self.macro_menu.subscription().map(BaseMessage::MacroMessage),
+        }
+    }
+
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         match message {
             BaseMessage::DismissError => self.curr_error = None,
@@ -0,0 +1,248 @@
+use anyhow::{Result, anyhow};
+use iced::{Length, Alignment, Command, window};
+use iced::image::Handle;
+use iced::pure::{Element, column, row, text, button, container};
+use image::RgbaImage;
+use image::io::Reader;
+use rfd::FileDialog;
+
+use crate::macro_logic::{Calibration, ImageTarget, Settings};
+
+use super::screen_capture::ScreenCapture;
+use super::style::TextButton;
+use super::widgets::{pixel_handle, PixelView};
+
+const THUMBNAIL_WIDTH: u16 = 160;
+const THUMBNAIL_HEIGHT: u16 = 90;
+
+#[derive(Debug, Clone)]
+pub enum CalibrationMessage {
+    Capture(bool),                  // true for the screenshots the images should be found on
+    Captured(bool, RgbaImage),
+    CaptureFailed(String),
+    AddFiles(bool),
+    Remove(bool, usize),
+    Calculate,
+    Apply,
+    Close
+}
+
+/// finds the accuracy of a step from sample screenshots, where its images should be found and where they must not be
+pub struct CalibrationTool {
+    pub step_index: usize,
+    target: ImageTarget,    // with the anchor, so only its region is searched like the step does
+    settings: Settings,     // of the macro, captures show what its steps search in
+    positives: Vec<(RgbaImage, Handle)>,
+    negatives: Vec<(RgbaImage, Handle)>,
+    capturing: bool,
+    calibration: Option<Calibration>    // None until calculated, and again after the samples change
+}
+
+impl CalibrationTool {
+    pub fn new(step_index: usize, target: ImageTarget, settings: Settings) -> Self {
+        Self { step_index, target, settings, positives: Vec::new(), negatives: Vec::new(), capturing: false, calibration: None }
+    }
+
+    /// everything except applying and closing, those are handled by the owner
+    pub fn update(&mut self, message: CalibrationMessage) -> Result<Command<CalibrationMessage>> {
+        match message {
+            CalibrationMessage::Capture(positive) => {
+                self.capturing = true;
+
+                return Ok(Command::perform(
+                    ScreenCapture::take_screenshot(self.settings.clone()),
+                    move |res| {
+                        match res {
+                            Ok(screenshot) => CalibrationMessage::Captured(positive, screenshot),
+                            Err(err) => CalibrationMessage::CaptureFailed(err.to_string()),
+                        }
+                    }
+                ))
+            },
+            CalibrationMessage::Captured(positive, screenshot) => {
+                self.capturing = false;
+                self.add_sample(positive, screenshot);
+            },
+            CalibrationMessage::CaptureFailed(error) => {
+                self.capturing = false;
+                return Err(anyhow!("Failed to take a screenshot:\n{}", error));
+            },
+            CalibrationMessage::AddFiles(positive) => {
+                let paths = match FileDialog::new().add_filter("pngs", &["png"]).pick_files() {
+                    Some(paths) => paths,
+                    None => return Ok(Command::none()),
+                };
+
+                for path in paths {
+                    let screenshot = Reader::open(&path)
+                        .map_err(|_| anyhow!("Failed to open {}", path.display()))?
+                        .decode()
+                        .map_err(|_| anyhow!("Could not read {}", path.display()))?
+                        .into_rgba();
+
+                    self.add_sample(positive, screenshot);
+                }
+            },
+            CalibrationMessage::Remove(positive, index) => {
+                let samples = if positive { &mut self.positives } else { &mut self.negatives };
+
+                if index < samples.len() {
+                    samples.remove(index);
+                    self.calibration = None;
+                }
+            },
+            CalibrationMessage::Calculate => {
+                let positives: Vec<RgbaImage> = self.positives.iter().map(|(screenshot, _)| screenshot.clone()).collect();
+                let negatives: Vec<RgbaImage> = self.negatives.iter().map(|(screenshot, _)| screenshot.clone()).collect();
+
+                self.calibration = Some(Calibration::new(&self.target, &positives, &negatives)?);
+            },
+            CalibrationMessage::Apply | CalibrationMessage::Close => (),
+        }
+
+        Ok(Command::none())
+    }
+
+    fn add_sample(&mut self, positive: bool, screenshot: RgbaImage) {
+        let handle = pixel_handle(&screenshot, 1);
+
+        if positive {
+            self.positives.push((screenshot, handle));
+        } else {
+            self.negatives.push((screenshot, handle));
+        }
+
+        self.calibration = None;
+    }
+
+    /// the allowed difference to write into the step
+    pub fn suggestion(&self) -> Option<f32> {
+        self.calibration.as_ref()?.suggestion()
+    }
+
+    pub fn window_mode(&self) -> window::Mode {
+        match self.capturing {
+            true => window::Mode::Hidden,
+            false => window::Mode::Windowed,
+        }
+    }
+
+    pub fn view(&self) -> Element<CalibrationMessage> {
+        if self.capturing {
+            return text("").into();
+        }
+
+        let mut bottom = row().push(
+            button(
+                text("Calculate")
+            )
+            .on_press(CalibrationMessage::Calculate)
+        );
+
+        if let Some(suggestion) = self.suggestion() {
+            bottom = bottom.push(
+                button(
+                    text(format!("Use {:.0}%", (1.0 - suggestion) * 100.0))
+                )
+                .on_press(CalibrationMessage::Apply)
+            );
+        }
+
+        column().push(
+            text(format!("Accuracy of step {} from sample screenshots", self.step_index + 1))
+        ).push(
+            samples_view("Should be found on", true, &self.positives)
+        ).push(
+            samples_view("Must not be found on", false, &self.negatives)
+        ).push(
+            text(self.result_text())
+        ).push(
+            bottom.push(
+                button(
+                    text("Close")
+                )
+                .on_press(CalibrationMessage::Close)
+            )
+            .spacing(10)
+        )
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+
+    fn result_text(&self) -> String {
+        let calibration = match &self.calibration {
+            Some(calibration) => calibration,
+            None => return "Add screenshots and press \"Calculate\"".to_string(),
+        };
+
+        // the step shows accuracy, which goes the other way than the allowed difference
+        match calibration.working_range() {
+            Some((lowest, highest)) => format!(
+                "Any accuracy from {}% to {}% works for all of the screenshots",
+                100 - highest, 100 - lowest
+            ),
+            None => {
+                let needed = format!("finding the image on all of the first screenshots needs {:.1}% at most", (1.0 - calibration.needed_by_positives()) * 100.0);
+
+                match calibration.closest_negative() {
+                    Some(closest) => format!(
+                        "No accuracy works - {}, but that already finds it on a screenshot it must not be found on ({:.1}% similar)",
+                        needed, (1.0 - closest) * 100.0
+                    ),
+                    None => format!("No accuracy works - {}", needed),
+                }
+            },
+        }
+    }
+}
+
+fn samples_view<'a>(label: &str, positive: bool, samples: &'a [(RgbaImage, Handle)]) -> Element<'a, CalibrationMessage> {
+    let mut thumbnails = row().spacing(5).align_items(Alignment::Center);
+
+    for (index, (screenshot, handle)) in samples.iter().enumerate() {
+        thumbnails = thumbnails.push(
+            column().push(
+                container(
+                    PixelView::from_handle(handle.clone(), screenshot.width(), screenshot.height(), 1)
+                )
+                .width(Length::Units(THUMBNAIL_WIDTH))
+                .height(Length::Units(THUMBNAIL_HEIGHT))
+                .center_x()
+                .center_y()
+            ).push(
+                button(
+                    text("Remove")
+                )
+                .on_press(CalibrationMessage::Remove(positive, index))
+                .style(TextButton::Normal)
+            )
+            .align_items(Alignment::Center)
+        );
+    }
+
+    column().push(
+        row().push(
+            text(label)
+        ).push(
+            button(
+                text("Capture screen")
+            )
+            .on_press(CalibrationMessage::Capture(positive))
+        ).push(
+            button(
+                text("Files...")
+            )
+            .on_press(CalibrationMessage::AddFiles(positive))
+        )
+        .spacing(10)
+        .align_items(Alignment::Center)
+    ).push(
+        container(
+            thumbnails
+        )
+        .height(Length::Units(THUMBNAIL_HEIGHT + 40))
+    )
+    .spacing(5)
+    .into()
+}
@@ -0,0 +1,152 @@
+use iced::{pure::{row, toggler, pick_list, container}, Alignment, Font, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+use image::RgbaImage;
+
+use crate::macro_logic::{Anchor, MatchMode, EnumInterString};
+
+use super::{image_input_component, percent_text_input, my_numeric_input};
+
+pub struct AnchorComponent<Message> {
+    anchor: Option<Anchor>,
+    on_change: Box<dyn Fn(Option<Anchor>) -> Message>,
+    on_capture: Box<dyn Fn() -> Message>,
+    on_edit: Box<dyn Fn() -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> AnchorComponent<Message> {
+    pub fn new(
+        anchor: Option<Anchor>,
+        on_change: impl Fn(Option<Anchor>) -> Message + 'static,
+        on_capture: impl Fn() -> Message + 'static,
+        on_edit: impl Fn() -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        AnchorComponent { anchor, on_change: Box::new(on_change), on_capture: Box::new(on_capture), on_edit: Box::new(on_edit), on_error: Box::new(on_error) }
+    }
+}
+
+#[derive(Clone)]
+pub enum ACEvent {
+    Toggle(bool),
+    ChangeImage(RgbaImage),
+    ChangeAllowedDifference(u32),
+    ChangeMatchMode(String),
+    ChangeX(i32),
+    ChangeY(i32),
+    ChangeWidth(u32),
+    ChangeHeight(u32),
+    CaptureImage,
+    EditImage,
+    EmitError(String)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for AnchorComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    type State = ();
+    type Event = ACEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            ACEvent::Toggle(enabled) => self.anchor = if enabled { Some(Default::default()) } else { None },
+            ACEvent::ChangeImage(image) => self.anchor.as_mut()?.image = Some(image),
+            ACEvent::ChangeAllowedDifference(percent) => self.anchor.as_mut()?.allowed_diff = ((100 - percent) as f32) / 100.0,
+            ACEvent::ChangeMatchMode(mode) => {
+                match MatchMode::from_str(&mode) {
+                    Ok(mode) => self.anchor.as_mut()?.mode = mode,
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            ACEvent::ChangeX(x) => self.anchor.as_mut()?.x = x,
+            ACEvent::ChangeY(y) => self.anchor.as_mut()?.y = y,
+            ACEvent::ChangeWidth(width) => self.anchor.as_mut()?.width = width.max(1),
+            ACEvent::ChangeHeight(height) => self.anchor.as_mut()?.height = height.max(1),
+            ACEvent::CaptureImage => return Some((self.on_capture)()),
+            ACEvent::EditImage => return Some((self.on_edit)()),
+            ACEvent::EmitError(error) => return Some((self.on_error)(error)),
+        }
+
+        Some((self.on_change)(self.anchor.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        let mut res = row().push(
+            container(
+                toggler(
+                    Some("Near anchor".to_string()),
+                    self.anchor.is_some(),
+                    ACEvent::Toggle
+                )
+            )
+            .width(Length::Units(140))
+        );
+
+        if let Some(anchor) = &self.anchor {
+            res = res.push(
+                container(
+                    image_input_component(
+                        anchor.image.clone(),
+                        ACEvent::ChangeImage,
+                        ACEvent::EmitError
+                    )
+                    .on_capture(|| ACEvent::CaptureImage)
+                    .on_edit(|| ACEvent::EditImage)
+                )
+                .width(Length::FillPortion(6))
+            )
+            .push(
+                container(
+                    percent_text_input(
+                        "0".into(),
+                        ((1.0 - anchor.allowed_diff) * 100.0).round() as u32,
+                        ACEvent::ChangeAllowedDifference
+                    )
+                )
+                .width(Length::Units(70))
+            )
+            .push(
+                pick_list(
+                    MatchMode::all_string_options(),
+                    Some(anchor.mode.to_string()),
+                    ACEvent::ChangeMatchMode
+                )
+            )
+            .push(my_numeric_input("x".to_string(), "x".to_string(), anchor.x, ACEvent::ChangeX))
+            .push(my_numeric_input("y".to_string(), "y".to_string(), anchor.y, ACEvent::ChangeY))
+            .push(my_numeric_input("width".to_string(), "w".to_string(), anchor.width, ACEvent::ChangeWidth))
+            .push(my_numeric_input("height".to_string(), "h".to_string(), anchor.height, ACEvent::ChangeHeight));
+        }
+
+        res
+            .spacing(3)
+            .align_items(Alignment::Center)
+            .into()
+    }
+}
+
+impl<'a, Message, Renderer> From<AnchorComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font> + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    fn from(anchor_component: AnchorComponent<Message>) -> Self {
+        pure::component(anchor_component)
+    }
+}
+
+pub fn anchor_component<Message>(
+    anchor: Option<Anchor>,
+    on_change: impl Fn(Option<Anchor>) -> Message + 'static,
+    on_capture: impl Fn() -> Message + 'static,
+    on_edit: impl Fn() -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> AnchorComponent<Message> {
+    AnchorComponent::new(anchor, on_change, on_capture, on_edit, on_error)
+}
@@ -0,0 +1,95 @@
+use autopilot::key::Flag;
+use autopilot::mouse::Button;
+use iced::{pure::{row, pick_list}, Alignment, Font};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+
+use crate::macro_logic::{ClickOptions, EnumInterString};
+
+use super::{my_numeric_input, modifiers_chooser_component};
+
+pub struct ClickOptionsComponent<Message> {
+    options: ClickOptions,
+    on_change: Box<dyn Fn(ClickOptions) -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> ClickOptionsComponent<Message> {
+    pub fn new(
+        options: ClickOptions,
+        on_change: impl Fn(ClickOptions) -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        ClickOptionsComponent { options, on_change: Box::new(on_change), on_error: Box::new(on_error) }
+    }
+}
+
+#[derive(Clone)]
+pub enum COCEvent {
+    ChangeButton(String),
+    ChangeCount(u32),
+    ChangeModifiers(Vec<Flag>)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for ClickOptionsComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static
+{
+    type State = ();
+    type Event = COCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            COCEvent::ChangeButton(button) => {
+                match Button::from_str(&button) {
+                    Ok(button) => self.options.button = button,
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            COCEvent::ChangeCount(count) => self.options.count = count.max(1),
+            COCEvent::ChangeModifiers(modifiers) => self.options.modifiers = modifiers,
+        }
+
+        Some((self.on_change)(self.options.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        row().push(
+            pick_list(
+                Button::all_string_options(),
+                Some(self.options.button.to_string()),
+                COCEvent::ChangeButton
+            )
+        ).push(
+            my_numeric_input("clicks".to_string(), "clicks".to_string(), self.options.count, COCEvent::ChangeCount)
+        ).push(
+            modifiers_chooser_component(
+                self.options.modifiers.clone(),
+                COCEvent::ChangeModifiers
+            )
+        )
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+}
+
+impl<'a, Message, Renderer> From<ClickOptionsComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font>
+{
+    fn from(click_options_component: ClickOptionsComponent<Message>) -> Self {
+        pure::component(click_options_component)
+    }
+}
+
+pub fn click_options_component<Message>(
+    options: ClickOptions,
+    on_change: impl Fn(ClickOptions) -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> ClickOptionsComponent<Message> {
+    ClickOptionsComponent::new(options, on_change, on_error)
+}
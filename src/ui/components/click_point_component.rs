@@ -0,0 +1,156 @@
+use iced::{pure::{button, column, row, toggler, pick_list, text}, Alignment, Font, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+
+use crate::macro_logic::{ClickPoint, OffsetUnit, EnumInterString};
+use crate::ui::style::TextButton;
+
+use super::my_numeric_input;
+
+pub struct ClickPointComponent<Message> {
+    point: ClickPoint,
+    on_change: Box<dyn Fn(ClickPoint) -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> ClickPointComponent<Message> {
+    pub fn new(
+        point: ClickPoint,
+        on_change: impl Fn(ClickPoint) -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        ClickPointComponent { point, on_change: Box::new(on_change), on_error: Box::new(on_error) }
+    }
+}
+
+#[derive(Clone)]
+pub enum CPCEvent {
+    NextPreset,
+    ToggleOffset(bool),
+    ChangeX(i32),
+    ChangeY(i32),
+    ChangeUnit(String)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for ClickPointComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static
+{
+    type State = ();
+    type Event = CPCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            CPCEvent::NextPreset => self.point = self.point.next(),
+            CPCEvent::ToggleOffset(is_offset) => {
+                self.point = if is_offset {
+                    // starts from where the preset was, so switching doesn't move the target
+                    let (x, y) = self.point.to_offset(1, 1);
+                    ClickPoint::Offset(x, y, OffsetUnit::Fraction)
+                } else {
+                    ClickPoint::CenterMiddle
+                }
+            },
+            CPCEvent::ChangeX(new_x) => {
+                match self.point {
+                    ClickPoint::Offset(ref mut x, _, unit) => *x = input_to_offset(new_x, unit),
+                    _ => unreachable!("CPCEvent::ChangeX dispatched when the inner value is {:?}", self.point)
+                }
+            },
+            CPCEvent::ChangeY(new_y) => {
+                match self.point {
+                    ClickPoint::Offset(_, ref mut y, unit) => *y = input_to_offset(new_y, unit),
+                    _ => unreachable!("CPCEvent::ChangeY dispatched when the inner value is {:?}", self.point)
+                }
+            },
+            CPCEvent::ChangeUnit(new_unit) => {
+                match self.point {
+                    ClickPoint::Offset(_, _, ref mut unit) => {
+                        match OffsetUnit::from_str(&new_unit) {
+                            Ok(new_unit) => *unit = new_unit,
+                            Err(err) => return Some((self.on_error)(err.to_string())),
+                        }
+                    },
+                    _ => unreachable!("CPCEvent::ChangeUnit dispatched when the inner value is {:?}", self.point)
+                }
+            },
+        }
+
+        Some((self.on_change)(self.point.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        let is_offset = matches!(self.point, ClickPoint::Offset(..));
+
+        let mut res = column().push(
+            row().push(
+                button(
+                    text("Next preset")
+                )
+                .on_press(CPCEvent::NextPreset)
+                .style(TextButton::Normal)
+            ).push(
+                toggler(
+                    Some("Offset".to_string()),
+                    is_offset,
+                    CPCEvent::ToggleOffset
+                )
+                .width(Length::Shrink)
+            )
+            .spacing(3)
+            .align_items(Alignment::Center)
+        );
+
+        if let ClickPoint::Offset(x, y, unit) = self.point {
+            res = res.push(
+                row()
+                    .push(my_numeric_input("x".to_string(), "x".to_string(), offset_to_input(x, unit), CPCEvent::ChangeX))
+                    .push(my_numeric_input("y".to_string(), "y".to_string(), offset_to_input(y, unit), CPCEvent::ChangeY))
+                    .spacing(3)
+            ).push(
+                pick_list(
+                    OffsetUnit::all_string_options(),
+                    Some(unit.to_string()),
+                    CPCEvent::ChangeUnit
+                )
+            );
+        }
+
+        res.spacing(3).into()
+    }
+}
+
+/// fractions are edited as whole percents
+fn offset_to_input(value: f64, unit: OffsetUnit) -> i32 {
+    match unit {
+        OffsetUnit::Pixels => value.round() as i32,
+        OffsetUnit::Fraction => (value * 100.0).round() as i32,
+    }
+}
+
+fn input_to_offset(value: i32, unit: OffsetUnit) -> f64 {
+    match unit {
+        OffsetUnit::Pixels => value as f64,
+        OffsetUnit::Fraction => value as f64 / 100.0,
+    }
+}
+
+impl<'a, Message, Renderer> From<ClickPointComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font>
+{
+    fn from(click_point_component: ClickPointComponent<Message>) -> Self {
+        pure::component(click_point_component)
+    }
+}
+
+pub fn click_point_component<Message>(
+    point: ClickPoint,
+    on_change: impl Fn(ClickPoint) -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> ClickPointComponent<Message> {
+    ClickPointComponent::new(point, on_change, on_error)
+}
@@ -0,0 +1,201 @@
+use std::{thread, time::Duration};
+
+use iced::{pure::{row, column, text, toggler, pick_list, button, container}, Alignment, Font, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+use image::RgbaImage;
+
+use crate::macro_logic::{ColorCheck, MatchMode, EnumInterString, Settings};
+use crate::ui::style::TextButton;
+
+use super::{image_input_component, percent_text_input, my_numeric_input};
+
+const PICK_DELAY: Duration = Duration::from_secs(3);
+
+pub struct ColorCheckComponent<Message> {
+    check: ColorCheck,
+    settings: Settings,     // of the macro, picked positions are relative to its target window
+    on_change: Box<dyn Fn(ColorCheck) -> Message>,
+    on_capture: Box<dyn Fn() -> Message>,
+    on_edit: Box<dyn Fn() -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> ColorCheckComponent<Message> {
+    pub fn new(
+        check: ColorCheck,
+        settings: Settings,
+        on_change: impl Fn(ColorCheck) -> Message + 'static,
+        on_capture: impl Fn() -> Message + 'static,
+        on_edit: impl Fn() -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        ColorCheckComponent { check, settings, on_change: Box::new(on_change), on_capture: Box::new(on_capture), on_edit: Box::new(on_edit), on_error: Box::new(on_error) }
+    }
+}
+
+#[derive(Clone)]
+pub enum CCCEvent {
+    ChangeRelative(bool),
+    ChangeImage(RgbaImage),
+    ChangeImageAllowedDifference(u32),
+    ChangeImageMatchMode(String),
+    ChangeX(i32),
+    ChangeY(i32),
+    ChangeSize(u32),
+    ChangeRed(u8),
+    ChangeGreen(u8),
+    ChangeBlue(u8),
+    ChangeAllowedDifference(u32),
+    PickFromCursor,
+    CaptureImage,
+    EditImage,
+    EmitError(String)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for ColorCheckComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    type State = ();
+    type Event = CCCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            CCCEvent::ChangeRelative(relative) => self.check.relative_to_image = relative,
+            CCCEvent::ChangeImage(image) => self.check.image = Some(image),
+            CCCEvent::ChangeImageAllowedDifference(percent) => self.check.image_allowed_diff = ((100 - percent) as f32) / 100.0,
+            CCCEvent::ChangeImageMatchMode(mode) => {
+                match MatchMode::from_str(&mode) {
+                    Ok(mode) => self.check.image_mode = mode,
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            CCCEvent::ChangeX(x) => self.check.x = x,
+            CCCEvent::ChangeY(y) => self.check.y = y,
+            CCCEvent::ChangeSize(size) => self.check.size = size.max(1),
+            CCCEvent::ChangeRed(red) => self.check.color[0] = red,
+            CCCEvent::ChangeGreen(green) => self.check.color[1] = green,
+            CCCEvent::ChangeBlue(blue) => self.check.color[2] = blue,
+            CCCEvent::ChangeAllowedDifference(percent) => self.check.allowed_diff = ((100 - percent) as f32) / 100.0,
+            CCCEvent::PickFromCursor => {
+                // gives the user time to move the mouse over the point, the ui is frozen meanwhile like when running a step
+                thread::sleep(PICK_DELAY);
+
+                if let Err(err) = self.check.pick_from_cursor(&self.settings) {
+                    return Some((self.on_error)("Couldn't pick the color:\n".to_string() + &err.to_string()));
+                }
+            },
+            CCCEvent::CaptureImage => return Some((self.on_capture)()),
+            CCCEvent::EditImage => return Some((self.on_edit)()),
+            CCCEvent::EmitError(error) => return Some((self.on_error)(error)),
+        }
+
+        Some((self.on_change)(self.check.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        let mut image_row = row().push(
+            container(
+                toggler(
+                    Some("Relative to image".to_string()),
+                    self.check.relative_to_image,
+                    CCCEvent::ChangeRelative
+                )
+            )
+            .width(Length::Units(200))
+        );
+
+        if self.check.relative_to_image {
+            image_row = image_row.push(
+                container(
+                    image_input_component(
+                        self.check.image.clone(),
+                        CCCEvent::ChangeImage,
+                        CCCEvent::EmitError
+                    )
+                    .on_capture(|| CCCEvent::CaptureImage)
+                    .on_edit(|| CCCEvent::EditImage)
+                )
+                .width(Length::FillPortion(6))
+            )
+            .push(
+                container(
+                    percent_text_input(
+                        "0".into(),
+                        ((1.0 - self.check.image_allowed_diff) * 100.0).round() as u32,
+                        CCCEvent::ChangeImageAllowedDifference
+                    )
+                )
+                .width(Length::Units(70))
+            )
+            .push(
+                pick_list(
+                    MatchMode::all_string_options(),
+                    Some(self.check.image_mode.to_string()),
+                    CCCEvent::ChangeImageMatchMode
+                )
+            );
+        }
+
+        let [red, green, blue] = self.check.color;
+
+        let color_row = row()
+            .push(my_numeric_input("x".to_string(), "x".to_string(), self.check.x, CCCEvent::ChangeX))
+            .push(my_numeric_input("y".to_string(), "y".to_string(), self.check.y, CCCEvent::ChangeY))
+            .push(my_numeric_input("size".to_string(), "px".to_string(), self.check.size, CCCEvent::ChangeSize))
+            .push(my_numeric_input("red".to_string(), "R".to_string(), red, CCCEvent::ChangeRed))
+            .push(my_numeric_input("green".to_string(), "G".to_string(), green, CCCEvent::ChangeGreen))
+            .push(my_numeric_input("blue".to_string(), "B".to_string(), blue, CCCEvent::ChangeBlue))
+            .push(
+                container(
+                    percent_text_input(
+                        "0".into(),
+                        ((1.0 - self.check.allowed_diff) * 100.0).round() as u32,
+                        CCCEvent::ChangeAllowedDifference
+                    )
+                )
+                .width(Length::Units(70))
+            )
+            .push(
+                button(
+                    text("Pick")
+                )
+                .on_press(CCCEvent::PickFromCursor)
+                .style(TextButton::Normal)
+            )
+            .spacing(3)
+            .align_items(Alignment::Center);
+
+        column()
+            .push(image_row.spacing(3).align_items(Alignment::Center))
+            .push(color_row)
+            .spacing(5)
+            .into()
+    }
+}
+
+impl<'a, Message, Renderer> From<ColorCheckComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font> + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    fn from(color_check_component: ColorCheckComponent<Message>) -> Self {
+        pure::component(color_check_component)
+    }
+}
+
+pub fn color_check_component<Message>(
+    check: ColorCheck,
+    settings: Settings,
+    on_change: impl Fn(ColorCheck) -> Message + 'static,
+    on_capture: impl Fn() -> Message + 'static,
+    on_edit: impl Fn() -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> ColorCheckComponent<Message> {
+    ColorCheckComponent::new(check, settings, on_change, on_capture, on_edit, on_error)
+}
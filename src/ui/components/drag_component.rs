@@ -0,0 +1,208 @@
+use autopilot::mouse::Button;
+use iced::{pure::{row, column, text, toggler, pick_list, container}, Alignment, Font, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+use image::RgbaImage;
+
+use crate::macro_logic::{Drag, DragEnd, ImageSlot, ClickPoint, MatchMode, EnumInterString};
+
+use super::{image_input_component, percent_text_input, my_numeric_input, click_point_component};
+
+pub struct DragComponent<Message> {
+    drag: Drag,
+    on_change: Box<dyn Fn(Drag) -> Message>,
+    on_capture: Box<dyn Fn(ImageSlot) -> Message>,
+    on_edit: Box<dyn Fn(ImageSlot) -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> DragComponent<Message> {
+    pub fn new(
+        drag: Drag,
+        on_change: impl Fn(Drag) -> Message + 'static,
+        on_capture: impl Fn(ImageSlot) -> Message + 'static,
+        on_edit: impl Fn(ImageSlot) -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        DragComponent { drag, on_change: Box::new(on_change), on_capture: Box::new(on_capture), on_edit: Box::new(on_edit), on_error: Box::new(on_error) }
+    }
+
+    /// 0 is the source, 1 the destination, same as the image slots of the step
+    fn end_mut(&mut self, index: usize) -> &mut DragEnd {
+        match index {
+            0 => &mut self.drag.source,
+            _ => &mut self.drag.destination,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum DCEvent {
+    ToggleImage(usize, bool),
+    ChangeImage(usize, RgbaImage),
+    ChangePoint(usize, ClickPoint),
+    ChangeAllowedDifference(usize, u32),
+    ChangeMatchMode(usize, String),
+    ChangeX(usize, i32),
+    ChangeY(usize, i32),
+    ChangeButton(String),
+    ChangePressHold(u64),
+    ChangeMoveTime(u64),
+    ChangeReleaseHold(u64),
+    CaptureImage(usize),
+    EditImage(usize),
+    EmitError(String)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for DragComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    type State = ();
+    type Event = DCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            DCEvent::ToggleImage(index, on_image) => self.end_mut(index).on_image = on_image,
+            DCEvent::ChangeImage(index, image) => self.end_mut(index).image = Some(image),
+            DCEvent::ChangePoint(index, point) => self.end_mut(index).point = point,
+            DCEvent::ChangeAllowedDifference(index, percent) => self.end_mut(index).allowed_diff = ((100 - percent) as f32) / 100.0,
+            DCEvent::ChangeMatchMode(index, mode) => {
+                match MatchMode::from_str(&mode) {
+                    Ok(mode) => self.end_mut(index).mode = mode,
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            DCEvent::ChangeX(index, x) => self.end_mut(index).x = x,
+            DCEvent::ChangeY(index, y) => self.end_mut(index).y = y,
+            DCEvent::ChangeButton(button) => {
+                match Button::from_str(&button) {
+                    Ok(button) => self.drag.button = button,
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            DCEvent::ChangePressHold(time) => self.drag.press_hold_ms = time,
+            DCEvent::ChangeMoveTime(time) => self.drag.move_ms = time,
+            DCEvent::ChangeReleaseHold(time) => self.drag.release_hold_ms = time,
+            DCEvent::CaptureImage(index) => return Some((self.on_capture)(ImageSlot::Template(index))),
+            DCEvent::EditImage(index) => return Some((self.on_edit)(ImageSlot::Template(index))),
+            DCEvent::EmitError(error) => return Some((self.on_error)(error)),
+        }
+
+        Some((self.on_change)(self.drag.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        let timing_row = row()
+            .push(
+                pick_list(
+                    Button::all_string_options(),
+                    Some(self.drag.button.to_string()),
+                    DCEvent::ChangeButton
+                )
+            )
+            .push(my_numeric_input("hold before moving".to_string(), "ms held".to_string(), self.drag.press_hold_ms, DCEvent::ChangePressHold))
+            .push(my_numeric_input("moving time".to_string(), "ms moving".to_string(), self.drag.move_ms, DCEvent::ChangeMoveTime))
+            .push(my_numeric_input("hold before releasing".to_string(), "ms held".to_string(), self.drag.release_hold_ms, DCEvent::ChangeReleaseHold))
+            .spacing(3)
+            .align_items(Alignment::Center);
+
+        column()
+            .push(end_row("From", 0, &self.drag.source))
+            .push(end_row("To", 1, &self.drag.destination))
+            .push(timing_row)
+            .spacing(5)
+            .into()
+    }
+}
+
+fn end_row<'a, Renderer>(label: &str, index: usize, end: &DragEnd) -> Element<'a, DCEvent, Renderer>
+where
+    Renderer: text::Renderer<Font = Font> + 'static + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    let mut res = row().push(
+        container(
+            text(label)
+        )
+        .width(Length::Units(40))
+    ).push(
+        container(
+            toggler(
+                Some("On image".to_string()),
+                end.on_image,
+                move |on_image| DCEvent::ToggleImage(index, on_image)
+            )
+        )
+        .width(Length::Units(120))
+    );
+
+    if end.on_image {
+        res = res.push(
+            container(
+                image_input_component(
+                    end.image.clone(),
+                    move |image| DCEvent::ChangeImage(index, image),
+                    DCEvent::EmitError
+                )
+                .click_point(end.point.clone(), move |point| DCEvent::ChangePoint(index, point))
+                .on_capture(move || DCEvent::CaptureImage(index))
+                .on_edit(move || DCEvent::EditImage(index))
+            )
+            .width(Length::FillPortion(6))
+        )
+        .push(
+            click_point_component(end.point.clone(), move |point| DCEvent::ChangePoint(index, point), DCEvent::EmitError)
+        )
+        .push(
+            container(
+                percent_text_input(
+                    "0".into(),
+                    ((1.0 - end.allowed_diff) * 100.0).round() as u32,
+                    move |percent| DCEvent::ChangeAllowedDifference(index, percent)
+                )
+            )
+            .width(Length::Units(70))
+        )
+        .push(
+            pick_list(
+                MatchMode::all_string_options(),
+                Some(end.mode.to_string()),
+                move |mode| DCEvent::ChangeMatchMode(index, mode)
+            )
+        );
+    } else {
+        res = res
+            .push(my_numeric_input("x".to_string(), "x".to_string(), end.x, move |x| DCEvent::ChangeX(index, x)))
+            .push(my_numeric_input("y".to_string(), "y".to_string(), end.y, move |y| DCEvent::ChangeY(index, y)));
+    }
+
+    res
+        .spacing(3)
+        .align_items(Alignment::Center)
+        .into()
+}
+
+impl<'a, Message, Renderer> From<DragComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font> + iced_native::svg::Renderer + iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    fn from(drag_component: DragComponent<Message>) -> Self {
+        pure::component(drag_component)
+    }
+}
+
+pub fn drag_component<Message>(
+    drag: Drag,
+    on_change: impl Fn(Drag) -> Message + 'static,
+    on_capture: impl Fn(ImageSlot) -> Message + 'static,
+    on_edit: impl Fn(ImageSlot) -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> DragComponent<Message> {
+    DragComponent::new(drag, on_change, on_capture, on_edit, on_error)
+}
@@ -1,34 +1,71 @@
-use iced::{pure::{row, image, button, text, container}, Length, Alignment};
-use iced::pure::widget::image::Handle;
+use iced::{pure::{row, button, text, container}, Length, Alignment};
 use iced_pure::Element;
 use iced_lazy::pure::{self, Component};
 use iced_native;
 use clippers::{ClipperData, Clipboard};
-use image::RgbImage;
+use image::RgbaImage;
 use image::io::Reader;
 
 use rfd::FileDialog;
 
+use crate::macro_logic::{ClickPoint, OffsetUnit};
+use crate::ui::widgets::pixel_view;
+
+const PREVIEW_HEIGHT: u32 = 100;
+const MAX_PREVIEW_ZOOM: u32 = 8;
+
 pub struct ImageInputComponent<Message> {
-    image: Option<RgbImage>,
-    on_change: Box<dyn Fn(RgbImage) -> Message>,
+    image: Option<RgbaImage>,
+    click_point: Option<ClickPoint>,
+    on_change: Box<dyn Fn(RgbaImage) -> Message>,
+    on_point_change: Option<Box<dyn Fn(ClickPoint) -> Message>>,
+    on_capture: Option<Box<dyn Fn() -> Message>>,
+    on_edit: Option<Box<dyn Fn() -> Message>>,
     on_error: Box<dyn Fn(String) -> Message>
 }
 
 impl<Message> ImageInputComponent<Message> {
     pub fn new(
-        image: Option<RgbImage>,
-        on_change: impl Fn(RgbImage) -> Message + 'static,
+        image: Option<RgbaImage>,
+        on_change: impl Fn(RgbaImage) -> Message + 'static,
         on_error: impl Fn(String) -> Message + 'static
     ) -> Self {
-        ImageInputComponent { image, on_change: Box::new(on_change), on_error: Box::new(on_error) }
+        ImageInputComponent { image, click_point: None, on_change: Box::new(on_change), on_point_change: None, on_capture: None, on_edit: None, on_error: Box::new(on_error) }
+    }
+
+    /// shows the point on the preview and lets the user pick it by clicking on the image
+    pub fn click_point(self, click_point: ClickPoint, on_point_change: impl Fn(ClickPoint) -> Message + 'static) -> Self {
+        Self {
+            click_point: Some(click_point),
+            on_point_change: Some(Box::new(on_point_change)),
+            ..self
+        }
+    }
+
+    /// shows the "Capture" button, the screenshot is taken and cut by whoever gets the message
+    pub fn on_capture(self, on_capture: impl Fn() -> Message + 'static) -> Self {
+        Self {
+            on_capture: Some(Box::new(on_capture)),
+            ..self
+        }
+    }
+
+    /// shows the "Edit" button when there is an image, the editor is opened by whoever gets the message
+    pub fn on_edit(self, on_edit: impl Fn() -> Message + 'static) -> Self {
+        Self {
+            on_edit: Some(Box::new(on_edit)),
+            ..self
+        }
     }
 }
 
 #[derive(Clone)]
 pub enum IICEvent {
     OpenImage,
-    PasteClipboard
+    PasteClipboard,
+    Capture,
+    Edit,
+    PickPoint(f64, f64)
 }
 
 impl<Message, Renderer> Component<Message, Renderer> for ImageInputComponent<Message>
@@ -53,7 +90,7 @@ where
 
                 if let Ok(png_file) = Reader::open(img_path) {
                     if let Ok(png_data) = png_file.decode() {
-                        self.image = Some(png_data.into_rgb());
+                        self.image = Some(png_data.into_rgba());
                     } else {
                         return Some((self.on_error)("Could not read the image".to_string()));
                     }
@@ -63,11 +100,10 @@ where
             },
             IICEvent::PasteClipboard => {
                 if let Some(ClipperData::Image(img_data)) = Clipboard::get().read() {
-                    if let Some(img_buffer) = RgbImage::from_raw(   // there might be a better way to do this
+                    if let Some(img_buffer) = RgbaImage::from_raw(   // there might be a better way to do this
                         img_data.width() as u32, 
                         img_data.height() as u32,
-                                                                        // remove the alpha channel
-                        img_data.pixels().flat_map(|pix| [pix[0], pix[1], pix[2]]).collect()
+                        img_data.pixels().flat_map(|pix| [pix[0], pix[1], pix[2], pix[3]]).collect()
                     ) {
                         self.image = Some(img_buffer);
 
@@ -78,6 +114,21 @@ where
                     return Some((self.on_error)("Clipboard does not contain an image".to_string()))
                 }
             },
+            IICEvent::Capture => return self.on_capture.as_ref().map(|on_capture| on_capture()),
+            IICEvent::Edit => return self.on_edit.as_ref().map(|on_edit| on_edit()),
+            IICEvent::PickPoint(x, y) => {
+                let (img, on_point_change) = (self.image.as_ref()?, self.on_point_change.as_ref()?);
+
+                // fractions stay fractions so the point keeps scaling with the image, pixel offsets are whole pixels
+                let point = match self.click_point {
+                    Some(ClickPoint::Offset(_, _, OffsetUnit::Fraction)) => ClickPoint::Offset(x / img.width() as f64, y / img.height() as f64, OffsetUnit::Fraction),
+                    _ => ClickPoint::Offset(x.floor(), y.floor(), OffsetUnit::Pixels)
+                };
+
+                self.click_point = Some(point.clone());
+
+                return Some(on_point_change(point));
+            },
         }
 
         // TODO: we need some proper error handling here!
@@ -93,15 +144,17 @@ where
         let mut res = row();
 
         if let Some(img) = &self.image {
-            let handle = Handle::from_pixels(
-                img.width(),
-                img.height(),
-                img.pixels().flat_map(|p| [p.0[2], p.0[1], p.0[0], 255]).collect()
-            );
+            let zoom = (PREVIEW_HEIGHT / img.height().max(1)).clamp(1, MAX_PREVIEW_ZOOM);
+            let mut preview = pixel_view(img, zoom)
+                .crosshair(self.click_point.as_ref().map(|point| point.to_offset(img.width(), img.height())));
+
+            if self.on_point_change.is_some() {
+                preview = preview.on_press(IICEvent::PickPoint);
+            }
 
             res = res.push(
                 container(
-                    image(handle)
+                    preview
                 )
                 .width(Length::Fill)
                 .center_x()
@@ -116,6 +169,34 @@ where
             )
         }
 
+        if self.image.is_some() && self.on_edit.is_some() {
+            res = res.push(
+                container(
+                    button(
+                        text(
+                            "Edit"
+                        )
+                    )
+                    .on_press(IICEvent::Edit)
+                )
+                .width(Length::Shrink)
+            );
+        }
+
+        if self.on_capture.is_some() {
+            res = res.push(
+                container(
+                    button(
+                        text(
+                            "Capture"
+                        )
+                    )
+                    .on_press(IICEvent::Capture)
+                )
+                .width(Length::Shrink)
+            );
+        }
+
         res.push(
             container(
                 button(
@@ -155,8 +236,8 @@ where
 }
 
 pub fn image_input_component<Message>(
-    image: Option<RgbImage>,
-    on_change: impl Fn(RgbImage) -> Message + 'static,
+    image: Option<RgbaImage>,
+    on_change: impl Fn(RgbaImage) -> Message + 'static,
     on_error: impl Fn(String) -> Message + 'static
 ) -> ImageInputComponent<Message> {
     ImageInputComponent::new(image, on_change, on_error)
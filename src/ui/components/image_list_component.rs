@@ -0,0 +1,187 @@
+use iced::{pure::{row, column, button, text, container}, Alignment, Color, Length, Rectangle};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native;
+use image::RgbaImage;
+
+use crate::macro_logic::ClickPoint;
+use crate::ui::style::TextButton;
+use crate::ui::widgets::pixel_view;
+
+use super::image_input_component;
+
+const THUMBNAIL_SIZE: u16 = 24;
+
+/// several alternative images of one step, the selected one is shown in an `ImageInputComponent`
+/// and the rest as a strip of thumbnails under it
+pub struct ImageListComponent<Message> {
+    images: Vec<RgbaImage>,
+    click_point: ClickPoint,
+    on_change: Box<dyn Fn(Vec<RgbaImage>) -> Message>,
+    on_point_change: Box<dyn Fn(ClickPoint) -> Message>,
+    on_capture: Box<dyn Fn(usize) -> Message>,
+    on_edit: Box<dyn Fn(usize) -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> ImageListComponent<Message> {
+    pub fn new(
+        images: Vec<RgbaImage>,
+        click_point: ClickPoint,
+        on_change: impl Fn(Vec<RgbaImage>) -> Message + 'static,
+        on_point_change: impl Fn(ClickPoint) -> Message + 'static,
+        on_capture: impl Fn(usize) -> Message + 'static,
+        on_edit: impl Fn(usize) -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        ImageListComponent {
+            images,
+            click_point,
+            on_change: Box::new(on_change),
+            on_point_change: Box::new(on_point_change),
+            on_capture: Box::new(on_capture),
+            on_edit: Box::new(on_edit),
+            on_error: Box::new(on_error)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ILCState {
+    selected: usize      // equal to the amount of images when a new one is being added
+}
+
+#[derive(Clone)]
+pub enum ILCEvent {
+    Select(usize),
+    Add,
+    RemoveSelected,
+    ChangeImage(RgbaImage),
+    ChangePoint(ClickPoint),
+    Capture,
+    Edit,
+    EmitError(String)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for ImageListComponent<Message>
+where
+    Renderer: iced_native::text::Renderer + iced_native::image::Renderer + 'static,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    type State = ILCState;
+    type Event = ILCEvent;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        // the list could have gotten shorter since the last event, e.g. when the step was replaced
+        state.selected = state.selected.min(self.images.len());
+
+        match event {
+            ILCEvent::Select(index) => {
+                state.selected = index.min(self.images.len());
+                None
+            },
+            ILCEvent::Add => {
+                state.selected = self.images.len();
+                None
+            },
+            ILCEvent::RemoveSelected => {
+                if state.selected < self.images.len() {
+                    self.images.remove(state.selected);
+                    state.selected = state.selected.saturating_sub(1);
+                }
+
+                Some((self.on_change)(self.images.clone()))
+            },
+            ILCEvent::ChangeImage(image) => {
+                match self.images.get_mut(state.selected) {
+                    Some(selected) => *selected = image,
+                    None => self.images.push(image),
+                }
+
+                Some((self.on_change)(self.images.clone()))
+            },
+            ILCEvent::ChangePoint(point) => Some((self.on_point_change)(point)),
+            ILCEvent::Capture => Some((self.on_capture)(state.selected)),
+            ILCEvent::Edit => Some((self.on_edit)(state.selected)),
+            ILCEvent::EmitError(error) => Some((self.on_error)(error)),
+        }
+    }
+
+    fn view(&self, state: &Self::State) -> Element<Self::Event, Renderer> {
+        let selected = state.selected.min(self.images.len());
+
+        let mut thumbnails = row().spacing(3).align_items(Alignment::Center);
+
+        for (i, image) in self.images.iter().enumerate() {
+            let mut thumbnail = pixel_view(image, 1).on_press(move |_, _| ILCEvent::Select(i));
+
+            if i == selected {
+                thumbnail = thumbnail.outline(
+                    Rectangle { x: 0.0, y: 0.0, width: image.width() as f32, height: image.height() as f32 },
+                    Color::from_rgb(1.0, 0.0, 0.0)
+                );
+            }
+
+            thumbnails = thumbnails.push(
+                container(thumbnail)
+                .width(Length::Units(THUMBNAIL_SIZE))
+                .height(Length::Units(THUMBNAIL_SIZE))
+                .center_x()
+                .center_y()
+            );
+        }
+
+        thumbnails = thumbnails.push(
+            button(text("+"))
+            .on_press(ILCEvent::Add)
+            .style(TextButton::Normal)
+        );
+
+        if selected < self.images.len() {
+            thumbnails = thumbnails.push(
+                button(text("Remove"))
+                .on_press(ILCEvent::RemoveSelected)
+                .style(TextButton::Normal)
+            );
+        }
+
+        column().push(
+            image_input_component(
+                self.images.get(selected).cloned(),
+                ILCEvent::ChangeImage,
+                ILCEvent::EmitError
+            )
+            .click_point(self.click_point.clone(), ILCEvent::ChangePoint)
+            .on_capture(|| ILCEvent::Capture)
+            .on_edit(|| ILCEvent::Edit)
+        ).push(
+            thumbnails
+        )
+        .spacing(3)
+        .into()
+    }
+}
+
+impl<'a, Message, Renderer> From<ImageListComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::text::Renderer + iced_native::image::Renderer + 'static,
+    <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
+{
+    fn from(image_list_component: ImageListComponent<Message>) -> Self {
+        pure::component(image_list_component)
+    }
+}
+
+pub fn image_list_component<Message>(
+    images: Vec<RgbaImage>,
+    click_point: ClickPoint,
+    on_change: impl Fn(Vec<RgbaImage>) -> Message + 'static,
+    on_point_change: impl Fn(ClickPoint) -> Message + 'static,
+    on_capture: impl Fn(usize) -> Message + 'static,
+    on_edit: impl Fn(usize) -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> ImageListComponent<Message> {
+    ImageListComponent::new(images, click_point, on_change, on_point_change, on_capture, on_edit, on_error)
+}
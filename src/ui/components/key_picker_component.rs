@@ -0,0 +1,138 @@
+use iced::{pure::{row, button, text, text_input, container}, Alignment, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native;
+
+use crate::macro_logic::{Key, EnumInterString};
+use crate::ui::style::TextButton;
+
+const MAX_MATCHES: usize = 8;
+
+/// picks a key by typing a part of its name, the list of all keys is too long for a drop-down
+pub struct KeyPickerComponent<Message> {
+    key: Key,
+    on_change: Box<dyn Fn(Key) -> Message>
+}
+
+impl<Message> KeyPickerComponent<Message> {
+    pub fn new(
+        key: Key,
+        on_change: impl Fn(Key) -> Message + 'static
+    ) -> Self {
+        KeyPickerComponent { key, on_change: Box::new(on_change) }
+    }
+}
+
+#[derive(Default)]
+pub struct KPCState {
+    search: String
+}
+
+#[derive(Clone)]
+pub enum KPCEvent {
+    Search(String),
+    Pick(Key),
+    PickFirst
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for KeyPickerComponent<Message>
+where
+    Renderer: iced_native::text::Renderer + 'static
+{
+    type State = KPCState;
+    type Event = KPCEvent;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        let key = match event {
+            KPCEvent::Search(search) => {
+                state.search = search;
+                return None;
+            },
+            KPCEvent::Pick(key) => key,
+            KPCEvent::PickFirst => *matching_keys(&state.search).first()?,
+        };
+
+        state.search.clear();
+
+        Some((self.on_change)(key))
+    }
+
+    fn view(&self, state: &Self::State) -> Element<Self::Event, Renderer> {
+        let mut res = row().push(
+            container(
+                text(self.key.to_string()).size(24)
+            )
+            .width(Length::Units(110))
+        ).push(
+            text_input(
+                "Search keys",
+                &state.search,
+                KPCEvent::Search
+            )
+            .on_submit(KPCEvent::PickFirst)
+            .width(Length::Units(120))
+        );
+
+        for key in matching_keys(&state.search) {
+            res = res.push(
+                button(
+                    text(key.to_string())
+                )
+                .on_press(KPCEvent::Pick(key))
+                .style(TextButton::Normal)
+            );
+        }
+
+        res
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+    }
+}
+
+/// keys whose name contains the search, the ones starting with it first - a single character is always a match
+/// so keys that aren't offered (e.g. letters with accents) can be picked too
+fn matching_keys(search: &str) -> Vec<Key> {
+    let search = search.trim().to_lowercase();
+
+    if search.is_empty() {
+        return Vec::new();
+    }
+
+    let names = Key::all_string_options();
+    let (mut starting, containing): (Vec<&String>, Vec<&String>) = names.iter()
+        .filter(|name| name.to_lowercase().contains(&search))
+        .partition(|name| name.to_lowercase().starts_with(&search));
+
+    starting.extend(containing);
+
+    let mut keys: Vec<Key> = Key::from_str(&search).into_iter().collect();
+
+    for key in starting.into_iter().filter_map(|name| Key::from_str(name).ok()) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    keys.truncate(MAX_MATCHES);
+
+    keys
+}
+
+impl<'a, Message, Renderer> From<KeyPickerComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::text::Renderer + 'static
+{
+    fn from(key_picker_component: KeyPickerComponent<Message>) -> Self {
+        pure::component(key_picker_component)
+    }
+}
+
+pub fn key_picker_component<Message>(
+    key: Key,
+    on_change: impl Fn(Key) -> Message + 'static
+) -> KeyPickerComponent<Message> {
+    KeyPickerComponent::new(key, on_change)
+}
@@ -0,0 +1,197 @@
+use iced::{pure::{row, column, text, text_input, toggler, button, container}, Alignment, Font, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+
+use crate::macro_logic::Launch;
+
+use super::{file_choose_component, my_numeric_input};
+
+pub struct LaunchComponent<Message> {
+    launch: Launch,
+    on_change: Box<dyn Fn(Launch) -> Message>
+}
+
+impl<Message> LaunchComponent<Message> {
+    pub fn new(
+        launch: Launch,
+        on_change: impl Fn(Launch) -> Message + 'static
+    ) -> Self {
+        LaunchComponent { launch, on_change: Box::new(on_change) }
+    }
+}
+
+#[derive(Clone)]
+pub enum LCEvent {
+    ChangeProgram(String),
+    ChangeArguments(String),
+    ChangeWorkingDir(String),
+    ChangeEnvName(usize, String),
+    ChangeEnvValue(usize, String),
+    AddEnv,
+    RemoveEnv(usize),
+    ToggleWait(bool),
+    ChangeTimeout(u64),
+    ToggleCheckExitCode(bool),
+    ToggleOpenWithDefault(bool)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for LaunchComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static
+{
+    type State = ();
+    type Event = LCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            LCEvent::ChangeProgram(program) => self.launch.program = program,
+            LCEvent::ChangeArguments(arguments) => self.launch.arguments = arguments,
+            LCEvent::ChangeWorkingDir(working_dir) => self.launch.working_dir = working_dir,
+            LCEvent::ChangeEnvName(index, name) => self.launch.env[index].0 = name,
+            LCEvent::ChangeEnvValue(index, value) => self.launch.env[index].1 = value,
+            LCEvent::AddEnv => self.launch.env.push(Default::default()),
+            LCEvent::RemoveEnv(index) => { self.launch.env.remove(index); },
+            LCEvent::ToggleWait(wait) => self.launch.wait = wait,
+            LCEvent::ChangeTimeout(timeout) => self.launch.timeout_seconds = timeout,
+            LCEvent::ToggleCheckExitCode(check) => self.launch.check_exit_code = check,
+            LCEvent::ToggleOpenWithDefault(open) => self.launch.open_with_default = open,
+        }
+
+        Some((self.on_change)(self.launch.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        let mut directory_row = row();
+
+        // an opened file gets no arguments of its own, the opener is given only the file
+        if !self.launch.open_with_default {
+            directory_row = directory_row.push(
+                text_input(
+                    "Arguments, quote the ones with spaces",
+                    &self.launch.arguments,
+                    LCEvent::ChangeArguments
+                )
+                .width(Length::FillPortion(6))
+            );
+        }
+
+        let mut res = column().push(
+            row().push(
+                file_choose_component(self.launch.program.clone(), LCEvent::ChangeProgram)
+            ).push(
+                container(
+                    toggler(
+                        Some("Open with default application".to_string()),
+                        self.launch.open_with_default,
+                        LCEvent::ToggleOpenWithDefault
+                    )
+                )
+                .width(Length::Units(280))
+            )
+            .spacing(10)
+            .align_items(Alignment::Center)
+        ).push(
+            directory_row.push(
+                text_input(
+                    "Working directory",
+                    &self.launch.working_dir,
+                    LCEvent::ChangeWorkingDir
+                )
+                .width(Length::FillPortion(4))
+            )
+            .spacing(5)
+        );
+
+        for (index, (name, value)) in self.launch.env.iter().enumerate() {
+            res = res.push(
+                row().push(
+                    text_input(
+                        "Variable",
+                        name,
+                        move |name| LCEvent::ChangeEnvName(index, name)
+                    )
+                    .width(Length::Units(150))
+                ).push(
+                    text("=")
+                ).push(
+                    text_input(
+                        "Value",
+                        value,
+                        move |value| LCEvent::ChangeEnvValue(index, value)
+                    )
+                ).push(
+                    button(
+                        text("x")
+                    )
+                    .on_press(LCEvent::RemoveEnv(index))
+                )
+                .spacing(5)
+                .align_items(Alignment::Center)
+            );
+        }
+
+        let mut wait_row = row().push(
+            button(
+                text("+ variable")
+            )
+            .on_press(LCEvent::AddEnv)
+        );
+
+        // the opener only hands the file over, so there's nothing to wait for
+        if !self.launch.open_with_default {
+            wait_row = wait_row.push(
+                container(
+                    toggler(
+                        Some("Wait for exit".to_string()),
+                        self.launch.wait,
+                        LCEvent::ToggleWait
+                    )
+                )
+                .width(Length::Units(150))
+            );
+        }
+
+        // both only matter while the step waits
+        if self.launch.wait && !self.launch.open_with_default {
+            wait_row = wait_row.push(
+                my_numeric_input("timeout".to_string(), "s".to_string(), self.launch.timeout_seconds, LCEvent::ChangeTimeout)
+            ).push(
+                container(
+                    toggler(
+                        Some("Fail on error code".to_string()),
+                        self.launch.check_exit_code,
+                        LCEvent::ToggleCheckExitCode
+                    )
+                )
+                .width(Length::Units(190))
+            );
+        }
+
+        res.push(
+            wait_row
+                .spacing(10)
+                .align_items(Alignment::Center)
+        )
+        .spacing(5)
+        .into()
+    }
+}
+
+impl<'a, Message, Renderer> From<LaunchComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font>
+{
+    fn from(launch_component: LaunchComponent<Message>) -> Self {
+        pure::component(launch_component)
+    }
+}
+
+pub fn launch_component<Message>(
+    launch: Launch,
+    on_change: impl Fn(Launch) -> Message + 'static
+) -> LaunchComponent<Message> {
+    LaunchComponent::new(launch, on_change)
+}
@@ -1,20 +1,22 @@
-use autopilot::{key::{Flag, KeyCode}, mouse::ScrollDirection};
+use autopilot::{key::Flag, mouse::ScrollDirection};
 use iced::{pure::{container, row, pick_list, text, button, column}, Length, Alignment, Font};
 use iced_pure::{Element, text_input};
 use iced_lazy::pure::{self, Component};
 use iced_native::text;
-use image::RgbImage;
+use image::RgbaImage;
 
-use crate::macro_logic::{MacroStep, EnumInterString, Settings};
-use crate::ui::style::{TextButton, BorderedContainer};
+use crate::macro_logic::{MacroStep, ImageSlot, EnumInterString, Settings, MatchMode, ColorCheck, ClickPoint, ClickOptions, Anchor, ImageTarget, Drag, Launch, Shell, VariableCheck, Key, parse_shortcut, format_shortcut};
+use crate::ui::style::BorderedContainer;
 
-use super::{file_choose_component, percent_text_input, image_input_component, my_numeric_input, modifiers_chooser_component};
+use super::{launch_component, shell_component, variable_check_component, percent_text_input, image_input_component, image_list_component, my_numeric_input, modifiers_chooser_component, color_check_component, click_point_component, anchor_component, click_options_component, drag_component, key_picker_component};
 
 pub struct MacroStepComponent<Message> {
     my_index: usize,
     value: MacroStep,
+    settings: Settings,     // of the whole macro, for running the step on its own
     on_change: Box<dyn Fn(MacroStep, usize) -> Message>,
     on_remove: Box<dyn Fn(usize) -> Message>,
+    on_action: Box<dyn Fn(usize, StepAction) -> Message>,
     on_error: Box<dyn Fn(String) -> Message>
 }
 
@@ -22,45 +24,86 @@ impl<Message> MacroStepComponent<Message> {
     pub fn new(
         step_index: usize,
         value: Option<MacroStep>,
+        settings: Settings,
         on_change: impl Fn(MacroStep, usize) -> Message + 'static,
         on_remove: impl Fn(usize) -> Message + 'static,
+        on_action: impl Fn(usize, StepAction) -> Message + 'static,
         on_error: impl Fn(String) -> Message + 'static
     ) -> Self {
         if let Some(value) = value {
-            return Self { my_index: step_index, value, on_change: Box::new(on_change), on_remove: Box::new(on_remove), on_error: Box::new(on_error) };
+            return Self { my_index: step_index, value, settings, on_change: Box::new(on_change), on_remove: Box::new(on_remove), on_action: Box::new(on_action), on_error: Box::new(on_error) };
         }
 
-        Self { my_index: step_index, value: Default::default(), on_change: Box::new(on_change), on_remove: Box::new(on_remove), on_error: Box::new(on_error) }
+        Self { my_index: step_index, value: Default::default(), settings, on_change: Box::new(on_change), on_remove: Box::new(on_remove), on_action: Box::new(on_action), on_error: Box::new(on_error) }
     }
 }
 
+/// what a step asks the macro menu to do for it, with the index of the step
+#[derive(Clone, Debug)]
+pub enum StepAction {
+    Capture(ImageSlot),
+    Edit(ImageSlot),
+    Test,
+    Calibrate,
+    Pick,
+    Record,
+    Expand(Vec<MacroStep>)      // replaces the step with several ones
+}
+
 #[derive(Clone)]
 pub enum MSCEvent {
     ChangeStepType(String),
-    ChangeCommand(String),
-    ChangeImage(RgbImage),
-    ChangePoint,
+    ChangeLaunch(Launch),
+    ChangeShell(Shell),
+    ChangeVariable(String),
+    ChangeVariableCheck(VariableCheck),
+    ChangeImage(RgbaImage),
+    ChangeImages(Vec<RgbaImage>),
+    ChangePoint(ClickPoint),
     ChangeAllowedDifference(u32),
+    ChangeMatchMode(String),
     ChangeTextType(String),
-    ChangeKey(String),
+    ChangeKey(Key),
     ChangeModifiers(Vec<Flag>),
     ChangeWaitTime(u64),
+    ChangeHoldTime(u64),
+    ChangeSettleTime(u64),
+    ChangeColorCheck(ColorCheck),
+    ChangeAnchor(Option<Anchor>),
+    ChangeClickOptions(ClickOptions),
+    ChangeDrag(Drag),
+    ChangeSkipCount(u32),
     ChangeScrollAmount(u32),
     ChangeScrollDirection(String),
+    ChangeX(i32),
+    ChangeY(i32),
     Remove,
+    CaptureImage(ImageSlot),
+    EditImage(ImageSlot),
     EmitError(String),
+    TestMatch,
+    Calibrate,
+    PickPosition,
+    ChangeShortcut(String),
+    SubmitShortcut,
+    RecordKeys,
     RunCurrentCommand
 }
 
+#[derive(Default)]
+pub struct MSCState {
+    shortcut: String    // typed in until it's submitted, the placeholder shows the current one
+}
+
 impl<Message, Renderer> Component<Message, Renderer> for MacroStepComponent<Message>
 where
     Renderer: text::Renderer<Font = Font> + 'static + iced_native::svg::Renderer + iced_native::image::Renderer,
     <Renderer as iced_native::image::Renderer>::Handle: From<iced::image::Handle>
 {
     type Event = MSCEvent;
-    type State = ();
+    type State = MSCState;
 
-    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
         match event {
             MSCEvent::ChangeStepType(new_type) => {
                 match MacroStep::from_str(&new_type) {
@@ -69,52 +112,85 @@ where
                 } 
             },
 
-            MSCEvent::ChangeCommand(new_command) => {
-                match &self.value {
-                    MacroStep::Launch(_) => self.value = MacroStep::Launch(new_command),
-                    _ => unreachable!("MSCEvent::ChangeCommand dispatched when the inner value is {:?}", self.value)
+            MSCEvent::ChangeLaunch(new_launch) => {
+                match self.value {
+                    MacroStep::Launch(ref mut launch) => *launch = new_launch,
+                    _ => unreachable!("MSCEvent::ChangeLaunch dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeShell(new_shell) => {
+                match self.value {
+                    MacroStep::RunShell(ref mut shell) => *shell = new_shell,
+                    _ => unreachable!("MSCEvent::ChangeShell dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeVariable(new_name) => {
+                match self.value {
+                    MacroStep::ReadClipboard(ref mut name) => *name = new_name,
+                    _ => unreachable!("MSCEvent::ChangeVariable dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeVariableCheck(new_check) => {
+                match self.value {
+                    MacroStep::CheckVariable(ref mut check) => *check = new_check,
+                    _ => unreachable!("MSCEvent::ChangeVariableCheck dispatched when the inner value is {:?}", self.value)
                 }
             },
 
             MSCEvent::ChangeImage(new_image) => {
-                match &self.value {
-                    MacroStep::ClickImage(_, click_point, allowed_difference) => self.value = MacroStep::ClickImage(Some(new_image), click_point.clone(), allowed_difference.clone()),
-                    MacroStep::MoveToImage(_, move_point, allowed_difference) => self.value = MacroStep::MoveToImage(Some(new_image), move_point.clone(), allowed_difference.clone()),
+                match self.value {
+                    MacroStep::WaitImageGone(ref mut image, ..) | MacroStep::SetClipboardImage(ref mut image) => *image = Some(new_image),
                     _ => unreachable!("MSCEvent::ChangeImage dispatched when the inner value is {:?}", self.value)
                 }
             },
 
-            MSCEvent::ChangePoint => {
-                match &self.value {
-                    MacroStep::ClickImage(image, old_point, allowed_difference) => self.value = MacroStep::ClickImage(image.clone(), old_point.next(), allowed_difference.clone()),
-                    MacroStep::MoveToImage(image, old_point, allowed_difference) => self.value = MacroStep::MoveToImage(image.clone(), old_point.next(), allowed_difference.clone()),
+            MSCEvent::ChangeImages(new_images) => {
+                match self.value {
+                    MacroStep::ClickImage(ImageTarget { ref mut images, .. }, _) | MacroStep::MoveToImage(ImageTarget { ref mut images, .. }) => *images = new_images,
+                    _ => unreachable!("MSCEvent::ChangeImages dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangePoint(new_point) => {
+                match self.value {
+                    MacroStep::ClickImage(ImageTarget { ref mut point, .. }, _) | MacroStep::MoveToImage(ImageTarget { ref mut point, .. }) => *point = new_point,
                     _ => unreachable!("MSCEvent::ChangePoint dispatched when the inner value is {:?}", self.value)
                 }
             },
 
             MSCEvent::ChangeAllowedDifference(new_allowed_diff) => {
-                match &self.value {
-                    MacroStep::ClickImage(image, click_point, _) => self.value = MacroStep::ClickImage(image.clone(), click_point.clone(), ((100 - new_allowed_diff) as f32) / 100.0),
-                    MacroStep::MoveToImage(image, move_point, _) => self.value = MacroStep::MoveToImage(image.clone(), move_point.clone(), ((100 - new_allowed_diff) as f32) / 100.0),
+                match self.value {
+                    MacroStep::ClickImage(ImageTarget { allowed_diff: ref mut allowed_difference, .. }, _) | MacroStep::MoveToImage(ImageTarget { allowed_diff: ref mut allowed_difference, .. }) | MacroStep::WaitImageGone(_, ref mut allowed_difference, ..) => *allowed_difference = ((100 - new_allowed_diff) as f32) / 100.0,
                     _ => unreachable!("MSCEvent::ChangeAllowedDifference dispatched when the inner value is {:?}", self.value)
                 }
             },
+
+            MSCEvent::ChangeMatchMode(new_mode) => {
+                match self.value {
+                    MacroStep::ClickImage(ImageTarget { ref mut mode, .. }, _) | MacroStep::MoveToImage(ImageTarget { ref mut mode, .. }) | MacroStep::WaitImageGone(_, _, ref mut mode, _) => {
+                        match MatchMode::from_str(&new_mode) {
+                            Ok(new_mode) => *mode = new_mode,
+                            Err(err) => return Some((self.on_error)(err.to_string())),
+                        }
+                    },
+                    _ => unreachable!("MSCEvent::ChangeMatchMode dispatched when the inner value is {:?}", self.value)
+                }
+            },
             
             MSCEvent::ChangeTextType(text) => {
                 match &self.value {
                     MacroStep::TypeText(_, flags) => self.value = MacroStep::TypeText(text, flags.to_vec()),
+                    MacroStep::SetClipboardText(_) => self.value = MacroStep::SetClipboardText(text),
                     _ => unreachable!("MSCEvent::ChangeTextType dispatched when the inner value is {:?}", self.value)
                 }
             },
 
-            MSCEvent::ChangeKey(key) => {
-                match &self.value {
-                    MacroStep::PressKey(_, flags) => {
-                        match KeyCode::from_str(&key) {
-                            Ok(key) => self.value = MacroStep::PressKey(key, flags.clone()),
-                            Err(err) => return Some((self.on_error)(err.to_string())),
-                        }
-                    },
+            MSCEvent::ChangeKey(new_key) => {
+                match self.value {
+                    MacroStep::PressKey(ref mut key, _) | MacroStep::KeyDown(ref mut key) | MacroStep::KeyUp(ref mut key) | MacroStep::HoldKey(ref mut key, _) => *key = new_key,
                     _ => unreachable!("MSCEvent::ChangeKey dispatched when the inner value is {:?}", self.value)
                 }
             }
@@ -122,7 +198,7 @@ where
             MSCEvent::ChangeModifiers(modifiers) => {
                 match &self.value {
                     MacroStep::TypeText(text, _) => self.value = MacroStep::TypeText(text.clone(), modifiers),
-                    MacroStep::PressKey(key, _) => self.value = MacroStep::PressKey(key.clone(), modifiers),
+                    MacroStep::PressKey(key, _) => self.value = MacroStep::PressKey(*key, modifiers),
                     _ => unreachable!("MSCEvent::ChangeModifiers dispatched when the inner value is {:?}", self.value)
                 }
             },
@@ -146,6 +222,20 @@ where
                 }
             }
 
+            MSCEvent::ChangeX(new_x) => {
+                match self.value {
+                    MacroStep::MoveTo(ref mut x, _) | MacroStep::MoveBy(ref mut x, _) | MacroStep::ClickAt(ref mut x, ..) => *x = new_x,
+                    _ => unreachable!("MSCEvent::ChangeX dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeY(new_y) => {
+                match self.value {
+                    MacroStep::MoveTo(_, ref mut y) | MacroStep::MoveBy(_, ref mut y) | MacroStep::ClickAt(_, ref mut y, _) => *y = new_y,
+                    _ => unreachable!("MSCEvent::ChangeY dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
             MSCEvent::ChangeWaitTime(time) => {
                 match &self.value {
                     MacroStep::WaitTime(_) => self.value = MacroStep::WaitTime(time),
@@ -153,14 +243,95 @@ where
                 }
             },
 
+            MSCEvent::ChangeHoldTime(time) => {
+                match self.value {
+                    MacroStep::HoldKey(_, ref mut hold_time) => *hold_time = time,
+                    _ => unreachable!("MSCEvent::ChangeHoldTime dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeSettleTime(time) => {
+                match self.value {
+                    MacroStep::WaitImageGone(_, _, _, ref mut settle_time) => *settle_time = time,
+                    _ => unreachable!("MSCEvent::ChangeSettleTime dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeColorCheck(new_check) => {
+                match self.value {
+                    MacroStep::WaitColor(ref mut check) | MacroStep::CheckColor(ref mut check, _) => *check = new_check,
+                    _ => unreachable!("MSCEvent::ChangeColorCheck dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeAnchor(new_anchor) => {
+                match self.value {
+                    MacroStep::ClickImage(ImageTarget { ref mut anchor, .. }, _) | MacroStep::MoveToImage(ImageTarget { ref mut anchor, .. }) => *anchor = new_anchor,
+                    _ => unreachable!("MSCEvent::ChangeAnchor dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeClickOptions(new_options) => {
+                match self.value {
+                    MacroStep::ClickImage(_, ref mut options) | MacroStep::ClickAt(.., ref mut options) => *options = new_options,
+                    _ => unreachable!("MSCEvent::ChangeClickOptions dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeDrag(new_drag) => {
+                match self.value {
+                    MacroStep::Drag(ref mut drag) => *drag = new_drag,
+                    _ => unreachable!("MSCEvent::ChangeDrag dispatched when the inner value is {:?}", self.value)
+                }
+            },
+
+            MSCEvent::ChangeSkipCount(count) => {
+                match self.value {
+                    MacroStep::CheckColor(_, ref mut skip_count) => *skip_count = count,
+                    _ => unreachable!("MSCEvent::ChangeSkipCount dispatched when the inner value is {:?}", self.value)
+                }
+            },
 
             MSCEvent::Remove => {
                 return Some((self.on_remove)(self.my_index));
             },
 
+            MSCEvent::CaptureImage(slot) => {
+                return Some((self.on_action)(self.my_index, StepAction::Capture(slot)));
+            },
+
+            MSCEvent::EditImage(slot) => {
+                return Some((self.on_action)(self.my_index, StepAction::Edit(slot)));
+            },
+
             MSCEvent::EmitError(error) => return Some((self.on_error)(error)),
+            MSCEvent::TestMatch => return Some((self.on_action)(self.my_index, StepAction::Test)),
+            MSCEvent::Calibrate => return Some((self.on_action)(self.my_index, StepAction::Calibrate)),
+            MSCEvent::PickPosition => return Some((self.on_action)(self.my_index, StepAction::Pick)),
+            MSCEvent::ChangeShortcut(shortcut) => {
+                state.shortcut = shortcut;
+                return None;
+            },
+            MSCEvent::SubmitShortcut => {
+                let mut steps: Vec<MacroStep> = match parse_shortcut(&state.shortcut) {
+                    Ok(chords) => chords.into_iter().map(|(key, flags)| MacroStep::PressKey(key, flags)).collect(),
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                };
+
+                state.shortcut.clear();
+
+                // a sequence becomes one step per combination in place of this one
+                if steps.len() > 1 {
+                    return Some((self.on_action)(self.my_index, StepAction::Expand(steps)));
+                }
+
+                self.value = steps.remove(0);
+            },
+            MSCEvent::RecordKeys => return Some((self.on_action)(self.my_index, StepAction::Record)),
             MSCEvent::RunCurrentCommand => {
-                if let Err(err) = self.value.dispatch(&Settings::new(10, false)) {
+                let settings = Settings { step_timeout_seconds: 10, break_whole_macro: false, ..self.settings.clone() };
+
+                if let Err(err) = self.value.run_alone(&settings) {
                     return Some((self.on_error)("An error occured while trying to execute the command:\n".to_string() + &err.to_string()))
                 }
             },
@@ -169,8 +340,10 @@ where
         Some((self.on_change)(self.value.clone(), self.my_index))
     }
 
-    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+    fn view(&self, state: &Self::State) -> Element<Self::Event, Renderer> {
         let mut res = row();
+        let mut second_row: Option<Element<Self::Event, Renderer>> = None;      // e.g. image steps have a second row for the anchor
+        let mut height = 150;
 
         res = res.push(
             container(
@@ -186,21 +359,28 @@ where
         );
 
         match &self.value {
-            MacroStep::Launch(path) => {
+            MacroStep::Launch(launch) => {
                 res = res.push(
                     container(
-                        file_choose_component(path.clone(), MSCEvent::ChangeCommand)
+                        launch_component(launch.clone(), MSCEvent::ChangeLaunch)
                     )
                     .width(Length::FillPortion(10))
-                )
+                );
+
+                // the program, the arguments and the waiting options, then a row for each variable
+                height = 160 + 40 * launch.env.len() as u16;
             },
 
-            MacroStep::ClickImage(curr_image, click_point, allowed_difference) => {
+            MacroStep::ClickImage(ImageTarget { images: curr_images, point: click_point, allowed_diff: allowed_difference, mode, anchor }, options) => {
                 res = res.push(
                     container(
-                        image_input_component(
-                            curr_image.clone(),
-                            MSCEvent::ChangeImage,
+                        image_list_component(
+                            curr_images.clone(),
+                            click_point.clone(),
+                            MSCEvent::ChangeImages,
+                            MSCEvent::ChangePoint,
+                            |index| MSCEvent::CaptureImage(ImageSlot::Template(index)),
+                            |index| MSCEvent::EditImage(ImageSlot::Template(index)),
                             MSCEvent::EmitError
                         )
                     )
@@ -208,11 +388,7 @@ where
                 )
                 .push(
                     container(
-                        button(
-                            click_point.svg()
-                        )
-                        .on_press(MSCEvent::ChangePoint)
-                        .style(TextButton::Normal)
+                        click_point_component(click_point.clone(), MSCEvent::ChangePoint, MSCEvent::EmitError)
                     )
                     .width(Length::Shrink)
                 )
@@ -227,14 +403,45 @@ where
                     )
                     .width(Length::Units(70))
                 )
+                .push(
+                    container(
+                        pick_list(
+                            MatchMode::all_string_options(),
+                            Some(mode.to_string()),
+                            MSCEvent::ChangeMatchMode
+                        )
+                    )
+                    .width(Length::Shrink)
+                );
+
+                second_row = Some(
+                    column().push(
+                        click_options_component(options.clone(), MSCEvent::ChangeClickOptions, MSCEvent::EmitError)
+                    ).push(
+                        anchor_component(
+                            anchor.clone(),
+                            MSCEvent::ChangeAnchor,
+                            || MSCEvent::CaptureImage(ImageSlot::Anchor),
+                            || MSCEvent::EditImage(ImageSlot::Anchor),
+                            MSCEvent::EmitError
+                        )
+                    )
+                    .spacing(5)
+                    .into()
+                );
+                height += if anchor.is_some() { 165 } else { 85 };
             },
 
-            MacroStep::MoveToImage(curr_image, move_point, allowed_difference) => {
+            MacroStep::MoveToImage(ImageTarget { images: curr_images, point: move_point, allowed_diff: allowed_difference, mode, anchor }) => {
                 res = res.push(
                     container(
-                        image_input_component(
-                            curr_image.clone(),
-                            MSCEvent::ChangeImage,
+                        image_list_component(
+                            curr_images.clone(),
+                            move_point.clone(),
+                            MSCEvent::ChangeImages,
+                            MSCEvent::ChangePoint,
+                            |index| MSCEvent::CaptureImage(ImageSlot::Template(index)),
+                            |index| MSCEvent::EditImage(ImageSlot::Template(index)),
                             MSCEvent::EmitError
                         )
                     )
@@ -242,11 +449,7 @@ where
                 )
                 .push(
                     container(
-                        button(
-                            move_point.svg()
-                        )
-                        .on_press(MSCEvent::ChangePoint)
-                        .style(TextButton::Normal)
+                        click_point_component(move_point.clone(), MSCEvent::ChangePoint, MSCEvent::EmitError)
                     )
                     .width(Length::Shrink)
                 )
@@ -261,7 +464,28 @@ where
                     )
                     .width(Length::Units(70))
                 )
-
+                .push(
+                    container(
+                        pick_list(
+                            MatchMode::all_string_options(),
+                            Some(mode.to_string()),
+                            MSCEvent::ChangeMatchMode
+                        )
+                    )
+                    .width(Length::Shrink)
+                );
+
+                second_row = Some(
+                    anchor_component(
+                        anchor.clone(),
+                        MSCEvent::ChangeAnchor,
+                        || MSCEvent::CaptureImage(ImageSlot::Anchor),
+                        || MSCEvent::EditImage(ImageSlot::Anchor),
+                        MSCEvent::EmitError
+                    )
+                    .into()
+                );
+                height += if anchor.is_some() { 120 } else { 40 };
             },
 
             MacroStep::TypeText(text, flags) => {
@@ -286,11 +510,7 @@ where
             MacroStep::PressKey(key, flags) => {
                 res = res.push(
                     container(
-                        pick_list(
-                            KeyCode::all_string_options(),
-                            Some(key.to_string()),
-                            MSCEvent::ChangeKey
-                        )
+                        key_picker_component(*key, MSCEvent::ChangeKey)
                     )
                     .width(Length::FillPortion(8))
                 ).push(
@@ -298,7 +518,29 @@ where
                         modifiers_chooser_component(flags.to_vec(), MSCEvent::ChangeModifiers)
                     )
                     .width(Length::Shrink)
-                )
+                );
+
+                second_row = Some(
+                    row().push(
+                        text_input(
+                            &format!("{}, or a sequence like alt+f, s", format_shortcut(key, flags)),
+                            &state.shortcut,
+                            MSCEvent::ChangeShortcut
+                        )
+                        .on_submit(MSCEvent::SubmitShortcut)
+                        .width(Length::Units(300))
+                    ).push(
+                        button(
+                            text("Record")
+                        )
+                        .on_press(MSCEvent::RecordKeys)
+                    )
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .into()
+                );
+
+                height += 40;
             },
 
             MacroStep::Scroll(direction, amount) => {
@@ -344,6 +586,269 @@ where
                     .width(Length::FillPortion(2))
                 )
             },
+
+            MacroStep::WaitImageGone(curr_image, allowed_difference, mode, settle_time) => {
+                res = res.push(
+                    container(
+                        image_input_component(
+                            curr_image.clone(),
+                            MSCEvent::ChangeImage,
+                            MSCEvent::EmitError
+                        )
+                        .on_capture(|| MSCEvent::CaptureImage(ImageSlot::Template(0)))
+                        .on_edit(|| MSCEvent::EditImage(ImageSlot::Template(0)))
+                    )
+                    .width(Length::FillPortion(6))
+                )
+                .push(
+                    container(
+                        percent_text_input(
+                            "0".into(), 
+                            ((1.0 - allowed_difference) * 100.0).round() as u32, 
+                            MSCEvent::ChangeAllowedDifference
+                        )
+                        .size(30)
+                    )
+                    .width(Length::Units(70))
+                )
+                .push(
+                    container(
+                        pick_list(
+                            MatchMode::all_string_options(),
+                            Some(mode.to_string()),
+                            MSCEvent::ChangeMatchMode
+                        )
+                    )
+                    .width(Length::Shrink)
+                )
+                .push(
+                    container(
+                        my_numeric_input(
+                            "Gone for at least".to_string(),
+                            "ms".to_string(),
+                            *settle_time,
+                            MSCEvent::ChangeSettleTime
+                        )
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(2))
+                )
+            },
+
+            MacroStep::WaitColor(check) => {
+                res = res.push(
+                    container(
+                        color_check_component(check.clone(), self.settings.clone(), MSCEvent::ChangeColorCheck, || MSCEvent::CaptureImage(ImageSlot::Template(0)), || MSCEvent::EditImage(ImageSlot::Template(0)), MSCEvent::EmitError)
+                    )
+                    .width(Length::FillPortion(10))
+                )
+            },
+
+            MacroStep::CheckColor(check, skip_count) => {
+                res = res.push(
+                    container(
+                        color_check_component(check.clone(), self.settings.clone(), MSCEvent::ChangeColorCheck, || MSCEvent::CaptureImage(ImageSlot::Template(0)), || MSCEvent::EditImage(ImageSlot::Template(0)), MSCEvent::EmitError)
+                    )
+                    .width(Length::FillPortion(8))
+                )
+                .push(
+                    container(
+                        my_numeric_input(
+                            "Steps to skip if different".to_string(),
+                            "skipped".to_string(),
+                            *skip_count,
+                            MSCEvent::ChangeSkipCount
+                        )
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(2))
+                )
+            },
+
+            MacroStep::MoveTo(x, y) | MacroStep::ClickAt(x, y, _) => {
+                res = res.push(
+                    container(
+                        my_numeric_input("x".to_string(), "x".to_string(), *x, MSCEvent::ChangeX)
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(4))
+                ).push(
+                    container(
+                        my_numeric_input("y".to_string(), "y".to_string(), *y, MSCEvent::ChangeY)
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(4))
+                ).push(
+                    button(
+                        text("Pick")
+                    )
+                    .on_press(MSCEvent::PickPosition)
+                );
+
+                if let MacroStep::ClickAt(_, _, options) = &self.value {
+                    res = res.push(
+                        container(
+                            click_options_component(options.clone(), MSCEvent::ChangeClickOptions, MSCEvent::EmitError)
+                        )
+                        .width(Length::Shrink)
+                    );
+                }
+            },
+
+            MacroStep::MoveBy(x, y) => {
+                res = res.push(
+                    container(
+                        my_numeric_input("pixels to the right".to_string(), "x".to_string(), *x, MSCEvent::ChangeX)
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(4))
+                ).push(
+                    container(
+                        my_numeric_input("pixels down".to_string(), "y".to_string(), *y, MSCEvent::ChangeY)
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(4))
+                )
+            },
+
+            MacroStep::Drag(drag) => {
+                res = res.push(
+                    container(
+                        drag_component(drag.clone(), MSCEvent::ChangeDrag, MSCEvent::CaptureImage, MSCEvent::EditImage, MSCEvent::EmitError)
+                    )
+                    .width(Length::FillPortion(10))
+                );
+
+                // a row of inputs for each end, a lot taller when it shows an image
+                height = 70 + [&drag.source, &drag.destination].iter().map(|end| if end.on_image { 120 } else { 40 }).sum::<u16>();
+            },
+
+            MacroStep::KeyDown(key) | MacroStep::KeyUp(key) => {
+                res = res.push(
+                    container(
+                        key_picker_component(*key, MSCEvent::ChangeKey)
+                    )
+                    .width(Length::FillPortion(8))
+                )
+            },
+
+            MacroStep::HoldKey(key, time) => {
+                res = res.push(
+                    container(
+                        key_picker_component(*key, MSCEvent::ChangeKey)
+                    )
+                    .width(Length::FillPortion(5))
+                ).push(
+                    container(
+                        my_numeric_input(
+                            "Time to hold in milliseconds".to_string(),
+                            "ms".to_string(),
+                            *time,
+                            MSCEvent::ChangeHoldTime
+                        )
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(5))
+                )
+            },
+
+            MacroStep::RunShell(shell) => {
+                res = res.push(
+                    container(
+                        shell_component(shell.clone(), MSCEvent::ChangeShell)
+                    )
+                    .width(Length::FillPortion(10))
+                );
+
+                height += 40;
+            },
+
+            MacroStep::SetClipboardText(text) => {
+                res = res.push(
+                    container(
+                        text_input(
+                            "Text to copy, ${name} is replaced by a variable",
+                            text,
+                            MSCEvent::ChangeTextType
+                        )
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(10))
+                )
+            },
+
+            MacroStep::SetClipboardImage(curr_image) => {
+                res = res.push(
+                    container(
+                        image_input_component(
+                            curr_image.clone(),
+                            MSCEvent::ChangeImage,
+                            MSCEvent::EmitError
+                        )
+                        .on_capture(|| MSCEvent::CaptureImage(ImageSlot::Template(0)))
+                        .on_edit(|| MSCEvent::EditImage(ImageSlot::Template(0)))
+                    )
+                    .width(Length::FillPortion(10))
+                )
+            },
+
+            MacroStep::ReadClipboard(name) => {
+                res = res.push(
+                    container(
+                        text("Text into")
+                    )
+                    .width(Length::Shrink)
+                ).push(
+                    container(
+                        text_input(
+                            "Variable",
+                            name,
+                            MSCEvent::ChangeVariable
+                        )
+                        .size(30)
+                    )
+                    .width(Length::FillPortion(10))
+                )
+            },
+
+            MacroStep::WaitClipboardChange => {
+                res = res.push(
+                    container(
+                        text("Until something else than what the macro last saw is copied")
+                    )
+                    .width(Length::FillPortion(10))
+                )
+            },
+
+            MacroStep::CheckVariable(check) => {
+                res = res.push(
+                    container(
+                        variable_check_component(check.clone(), MSCEvent::ChangeVariableCheck, MSCEvent::EmitError)
+                    )
+                    .width(Length::FillPortion(10))
+                );
+
+                height += 40;
+            },
+        }
+
+        if self.value.search_target().is_some() {
+            res = res.push(
+                button(
+                    text(
+                        "test"
+                    )
+                )
+                .on_press(MSCEvent::TestMatch)
+            )
+            .push(
+                button(
+                    text(
+                        "tune"
+                    )
+                )
+                .on_press(MSCEvent::Calibrate)
+            );
         }
 
         res = res.push(
@@ -355,23 +860,29 @@ where
             .on_press(MSCEvent::RunCurrentCommand)
         );
 
-        container(
-            res
-            .push(
-                column().push(
-                    button(
-                        text(
-                            "x"
-                        )
+        res = res.push(
+            column().push(
+                button(
+                    text(
+                        "x"
                     )
-                    .on_press(MSCEvent::Remove)
                 )
-                .height(Length::Fill)
+                .on_press(MSCEvent::Remove)
             )
-            .spacing(3)
-            .align_items(Alignment::Center)
+            .height(Length::Fill)
+        )
+        .spacing(3)
+        .align_items(Alignment::Center);
+
+        let content: Element<Self::Event, Renderer> = match second_row {
+            Some(second_row) => column().push(res).push(second_row).spacing(5).into(),
+            None => res.into(),
+        };
+
+        container(
+            content
         )
-        .height(Length::Units(150))     //replace this wiht max_hight when it's fixed in 0.5
+        .height(Length::Units(height))     //replace this wiht max_hight when it's fixed in 0.5
         .style(BorderedContainer::Nothing)
         .padding(8)
         .into()
@@ -393,10 +904,12 @@ where
 pub fn macro_step_component<Message>(
     step_index: usize,
     value: Option<MacroStep>,
+    settings: Settings,
     on_change: impl Fn(MacroStep, usize) -> Message + 'static,
     on_remove: impl Fn(usize) -> Message + 'static,
+    on_action: impl Fn(usize, StepAction) -> Message + 'static,
     on_error: impl Fn(String) -> Message + 'static
 ) -> MacroStepComponent<Message> {
-    MacroStepComponent::new(step_index, value, on_change, on_remove, on_error)
+    MacroStepComponent::new(step_index, value, settings, on_change, on_remove, on_action, on_error)
 }
 
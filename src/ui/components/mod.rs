@@ -2,14 +2,34 @@ mod macro_step_component;
 mod file_choose_component;
 mod percent_text_input;
 mod image_input_component;
+mod image_list_component;
 mod my_numeric_input;
 mod modifiers_chooser_component;
 mod settings_component;
+mod color_check_component;
+mod click_point_component;
+mod anchor_component;
+mod click_options_component;
+mod drag_component;
+mod key_picker_component;
+mod launch_component;
+mod shell_component;
+mod variable_check_component;
 
-pub use macro_step_component::macro_step_component;
+pub use macro_step_component::{macro_step_component, StepAction};
 pub use file_choose_component::file_choose_component;
 pub use percent_text_input::percent_text_input;
 pub use image_input_component::image_input_component;
+pub use image_list_component::image_list_component;
 pub use my_numeric_input::my_numeric_input;
 pub use modifiers_chooser_component::modifiers_chooser_component;
-pub use settings_component::settings_component;
\ No newline at end of file
+pub use settings_component::settings_component;
+pub use color_check_component::color_check_component;
+pub use click_point_component::click_point_component;
+pub use anchor_component::anchor_component;
+pub use click_options_component::click_options_component;
+pub use drag_component::drag_component;
+pub use key_picker_component::key_picker_component;
+pub use launch_component::launch_component;
+pub use shell_component::shell_component;
+pub use variable_check_component::variable_check_component;
\ No newline at end of file
@@ -21,7 +21,8 @@ impl<Message> ModifiersChooserComponent<Message> {
 pub enum MCCEvent {
     CtrlFlagFlipped(bool),
     AltFlagFlipped(bool),
-    ShiftFlagFlipped(bool)
+    ShiftFlagFlipped(bool),
+    MetaFlagFlipped(bool)
 }
 
 impl<Message, Renderer> Component<Message, Renderer> for ModifiersChooserComponent<Message>
@@ -58,6 +59,13 @@ where
                     self.flags.remove(self.flags.iter().position(|elem| *elem == Flag::Shift).unwrap());  // flag has to always exist at this point
                 }
             },
+            MCCEvent::MetaFlagFlipped(flag) => {
+                if flag {
+                    self.flags.push(Flag::Meta);
+                } else {
+                    self.flags.remove(self.flags.iter().position(|elem| *elem == Flag::Meta).unwrap());  // flag has to always exist at this point
+                }
+            },
         }
 
         Some((self.on_change)(self.flags.clone()))
@@ -90,6 +98,13 @@ where
                     MCCEvent::ShiftFlagFlipped
                 )
                 .width(Length::Shrink)
+            ).push(
+                toggler(
+                    Some("Meta".to_string()),
+                    self.flags.contains(&Flag::Meta),
+                    MCCEvent::MetaFlagFlipped
+                )
+                .width(Length::Shrink)
             )
         )
         .align_items(Alignment::Center)
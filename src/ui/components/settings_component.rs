@@ -27,7 +27,8 @@ impl<Message> SettingsComponent<Message> {
 
 pub enum SCEvent {
     BreakWholeMacroChanged(bool),
-    StepTimeoutChanges(u64)
+    StepTimeoutChanges(u64),
+    SearchIntervalChanges(u64)
 }
 
 impl<Message, Renderer> Component<Message, Renderer> for SettingsComponent<Message>
@@ -45,6 +46,7 @@ where
         match event {
             SCEvent::BreakWholeMacroChanged(break_whole_macro) => self.settings.break_whole_macro = break_whole_macro,
             SCEvent::StepTimeoutChanges(timeout) => self.settings.step_timeout_seconds = timeout,
+            SCEvent::SearchIntervalChanges(interval) => self.settings.search_interval_ms = interval,
         }
 
         Some((self.on_change)(self.settings.clone()))
@@ -68,6 +70,17 @@ where
                     SCEvent::StepTimeoutChanges
                 )
             )
+        ).push(
+            row().push(
+                text("Pause between searches")
+            ).push(
+                my_numeric_input(
+                    "milliseconds".to_string(), 
+                    "ms".to_string(), 
+                    self.settings.search_interval_ms, 
+                    SCEvent::SearchIntervalChanges
+                )
+            )
         ).into()
     }
 }
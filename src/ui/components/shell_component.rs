@@ -0,0 +1,114 @@
+use iced::{pure::{row, column, text, text_input}, Alignment, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+
+use crate::macro_logic::Shell;
+
+use super::my_numeric_input;
+
+pub struct ShellComponent<Message> {
+    shell: Shell,
+    on_change: Box<dyn Fn(Shell) -> Message>
+}
+
+impl<Message> ShellComponent<Message> {
+    pub fn new(
+        shell: Shell,
+        on_change: impl Fn(Shell) -> Message + 'static
+    ) -> Self {
+        ShellComponent { shell, on_change: Box::new(on_change) }
+    }
+}
+
+#[derive(Clone)]
+pub enum SHCEvent {
+    ChangeCommand(String),
+    ChangeStdoutVariable(String),
+    ChangeStderrVariable(String),
+    ChangeStatusVariable(String),
+    ChangeTimeout(u64)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for ShellComponent<Message>
+where
+    Renderer: text::Renderer + 'static
+{
+    type State = ();
+    type Event = SHCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            SHCEvent::ChangeCommand(command) => self.shell.command = command,
+            SHCEvent::ChangeStdoutVariable(name) => self.shell.stdout_variable = name,
+            SHCEvent::ChangeStderrVariable(name) => self.shell.stderr_variable = name,
+            SHCEvent::ChangeStatusVariable(name) => self.shell.status_variable = name,
+            SHCEvent::ChangeTimeout(timeout) => self.shell.timeout_seconds = timeout,
+        }
+
+        Some((self.on_change)(self.shell.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        column().push(
+            row().push(
+                text_input(
+                    "Command line, e.g. ./new-ticket.sh --project X",
+                    &self.shell.command,
+                    SHCEvent::ChangeCommand
+                )
+            ).push(
+                my_numeric_input("timeout".to_string(), "s".to_string(), self.shell.timeout_seconds, SHCEvent::ChangeTimeout)
+            )
+            .spacing(5)
+            .align_items(Alignment::Center)
+        ).push(
+            // the names the output is used by in other steps, as ${name}
+            row()
+                .push(variable_input("Output to", &self.shell.stdout_variable, SHCEvent::ChangeStdoutVariable))
+                .push(variable_input("Errors to", &self.shell.stderr_variable, SHCEvent::ChangeStderrVariable))
+                .push(variable_input("Exit code to", &self.shell.status_variable, SHCEvent::ChangeStatusVariable))
+                .spacing(10)
+                .align_items(Alignment::Center)
+        )
+        .spacing(5)
+        .into()
+    }
+}
+
+fn variable_input<'a, Renderer>(label: &str, name: &str, on_change: fn(String) -> SHCEvent) -> Element<'a, SHCEvent, Renderer>
+where
+    Renderer: text::Renderer + 'static
+{
+    row().push(
+        text(label)
+    ).push(
+        text_input(
+            "not kept",
+            name,
+            on_change
+        )
+        .width(Length::Units(120))
+    )
+    .spacing(5)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+impl<'a, Message, Renderer> From<ShellComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer
+{
+    fn from(shell_component: ShellComponent<Message>) -> Self {
+        pure::component(shell_component)
+    }
+}
+
+pub fn shell_component<Message>(
+    shell: Shell,
+    on_change: impl Fn(Shell) -> Message + 'static
+) -> ShellComponent<Message> {
+    ShellComponent::new(shell, on_change)
+}
@@ -0,0 +1,136 @@
+use iced::{pure::{row, column, text, text_input, pick_list}, Alignment, Font, Length};
+use iced_pure::Element;
+use iced_lazy::pure::{self, Component};
+use iced_native::text;
+
+use crate::macro_logic::{VariableCheck, Comparison, CheckFailure, EnumInterString};
+
+use super::my_numeric_input;
+
+pub struct VariableCheckComponent<Message> {
+    check: VariableCheck,
+    on_change: Box<dyn Fn(VariableCheck) -> Message>,
+    on_error: Box<dyn Fn(String) -> Message>
+}
+
+impl<Message> VariableCheckComponent<Message> {
+    pub fn new(
+        check: VariableCheck,
+        on_change: impl Fn(VariableCheck) -> Message + 'static,
+        on_error: impl Fn(String) -> Message + 'static
+    ) -> Self {
+        VariableCheckComponent { check, on_change: Box::new(on_change), on_error: Box::new(on_error) }
+    }
+}
+
+#[derive(Clone)]
+pub enum VCCEvent {
+    ChangeName(String),
+    ChangeComparison(String),
+    ChangeValue(String),
+    ChangeFailure(String),
+    ChangeSkipCount(u32)
+}
+
+impl<Message, Renderer> Component<Message, Renderer> for VariableCheckComponent<Message>
+where
+    Renderer: text::Renderer<Font = Font> + 'static
+{
+    type State = ();
+    type Event = VCCEvent;
+
+    fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            VCCEvent::ChangeName(name) => self.check.name = name,
+            VCCEvent::ChangeComparison(comparison) => {
+                match Comparison::from_str(&comparison) {
+                    Ok(comparison) => self.check.comparison = comparison,
+                    Err(err) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            VCCEvent::ChangeValue(value) => self.check.value = value,
+            VCCEvent::ChangeFailure(failure) => {
+                match (CheckFailure::from_str(&failure), self.check.on_fail) {
+                    // picking skipping again keeps the count
+                    (Ok(CheckFailure::SkipNext(_)), CheckFailure::SkipNext(_)) => (),
+                    (Ok(failure), _) => self.check.on_fail = failure,
+                    (Err(err), _) => return Some((self.on_error)(err.to_string())),
+                }
+            },
+            VCCEvent::ChangeSkipCount(count) => self.check.on_fail = CheckFailure::SkipNext(count),
+        }
+
+        Some((self.on_change)(self.check.clone()))
+    }
+
+    fn view(&self, _state: &Self::State) -> Element<Self::Event, Renderer> {
+        let mut failure_row = row().push(
+            text("Otherwise")
+        ).push(
+            pick_list(
+                CheckFailure::all_string_options(),
+                Some(self.check.on_fail.to_string()),
+                VCCEvent::ChangeFailure
+            )
+        );
+
+        if let CheckFailure::SkipNext(skip_count) = self.check.on_fail {
+            failure_row = failure_row.push(
+                my_numeric_input("Steps to skip".to_string(), "skipped".to_string(), skip_count, VCCEvent::ChangeSkipCount)
+            );
+        }
+
+        column().push(
+            row().push(
+                text("If")
+            ).push(
+                text_input(
+                    "Variable",
+                    &self.check.name,
+                    VCCEvent::ChangeName
+                )
+                .width(Length::Units(120))
+            ).push(
+                pick_list(
+                    Comparison::all_string_options(),
+                    Some(self.check.comparison.to_string()),
+                    VCCEvent::ChangeComparison
+                )
+            ).push(
+                // compared as text, so an exit code is e.g. 0
+                text_input(
+                    "Value, can use ${name}",
+                    &self.check.value,
+                    VCCEvent::ChangeValue
+                )
+            )
+            .spacing(10)
+            .align_items(Alignment::Center)
+        ).push(
+            failure_row
+                .spacing(10)
+                .align_items(Alignment::Center)
+        )
+        .spacing(5)
+        .into()
+    }
+}
+
+impl<'a, Message, Renderer> From<VariableCheckComponent<Message>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'static + text::Renderer<Font = Font>
+{
+    fn from(variable_check_component: VariableCheckComponent<Message>) -> Self {
+        pure::component(variable_check_component)
+    }
+}
+
+pub fn variable_check_component<Message>(
+    check: VariableCheck,
+    on_change: impl Fn(VariableCheck) -> Message + 'static,
+    on_error: impl Fn(String) -> Message + 'static
+) -> VariableCheckComponent<Message> {
+    VariableCheckComponent::new(check, on_change, on_error)
+}
@@ -3,19 +3,32 @@ use std::sync::Mutex;
 
 use anyhow::anyhow;
 use anyhow::Result;
+use async_std::task::spawn_blocking;
 use iced::Alignment;
 use iced::Command;
+use iced::Subscription;
+use iced::keyboard::{self, KeyCode, Modifiers};
 use iced::Length;
 use iced::alignment::Horizontal;
 use iced::alignment::Vertical;
-use iced::pure::{Element, column, row, container, text, scrollable, button, toggler, text_input};
+use iced::window;
+use iced::pure::{Element, column, row, container, text, scrollable, button, toggler, text_input, pick_list};
 use iced_aw::pure::{Card, Modal};
+use iced_native::Event;
+use image::RgbaImage;
 use rfd::FileDialog;
 
 use crate::macro_logic::Macro;
-use crate::macro_logic::MacroStep;
+use crate::macro_logic::{MacroStep, ImageSlot};
+use crate::macro_logic::{MonitorChoice, WindowTarget, EnumInterString};
+use crate::macro_logic::{relative_cursor_position, wait_for_f8};
 
-use super::components::macro_step_component;
+use super::components::{macro_step_component, StepAction};
+use super::screen_capture::ScreenCapture;
+use super::template_editor::{TemplateEditor, TemplateEditorMessage};
+use super::match_debugger::{MatchDebugger, MatchReport};
+use super::calibration_tool::{CalibrationTool, CalibrationMessage};
+use super::recorded_keys::{recorded_key, recorded_flags};
 use super::style::BorderedContainer;
 use super::style::PlusButton;
 
@@ -23,15 +36,37 @@ use super::style::PlusButton;
 pub struct MacroMenu {
     pub macro_data: Macro,
     show_settings: bool,
+    monitor_options: Vec<String>,       // read when the settings are shown, enumerating monitors on every redraw is slow
     is_modified: bool,
     show_confimation: bool,
     macro_should_run: Arc<Mutex<bool>>,
     macro_is_running: Arc<Mutex<bool>>,
+    capture: Option<ScreenCapture>,
+    editor: Option<TemplateEditor>,
+    debugger: Option<MatchDebugger>,
+    calibration: Option<CalibrationTool>,
+    picking: Option<Picking>,
+    recording: Option<usize>    // key press step waiting for the keys to be pressed
+}
+
+/// a step waiting for F8 to take the mouse position
+struct Picking {
+    step_index: usize,
+    waiting: Arc<Mutex<bool>>,      // for F8 outside of the window
+    hotkey_error: Option<String>    // why F8 only works while the window has the focus
+}
+
+impl Drop for Picking {
+    // however the picking ends, F8 is given back to the other applications
+    fn drop(&mut self) {
+        *(self.waiting.lock().unwrap()) = false;
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum MacroMenuMessage {
     NewVal(MacroStep, usize),
+    NewVals(Vec<MacroStep>, usize),
     Removed(usize),
     Add,
     EmitError(String),
@@ -43,8 +78,34 @@ pub enum MacroMenuMessage {
     SettingsShow,
     SettingsUpdateBreakWhileMacro(bool),
     SettingsUpdateStepTimeout(String),
+    SettingsUpdateSearchInterval(String),
+    SettingsUpdateMonitor(String),
+    SettingsUpdateWindow(bool),
+    SettingsUpdateWindowTitle(String),
+    SettingsUpdateWindowClass(String),
+    SettingsUpdateOpener(String),
     SettingsDismiss,
-    SavePressed
+    SavePressed,
+    CaptureFor(usize, ImageSlot),
+    CaptureTaken(RgbaImage),
+    CaptureSelectionStart(f64, f64),
+    CaptureSelectionMove(f64, f64),
+    CaptureSelectionEnd(f64, f64),
+    CaptureCancel,
+    EditImageFor(usize, ImageSlot),
+    Editor(TemplateEditorMessage),
+    TestMatchFor(usize),
+    TestMatchDone(MatchReport),
+    TestMatchClose,
+    CalibrateFor(usize),
+    Calibration(CalibrationMessage),
+    PickPositionFor(usize),
+    PickPositionTaken,
+    PickPositionHotkey(Result<bool, String>),   // true when F8 was pressed outside of the window
+    PickPositionCancel,
+    RecordKeysFor(usize),
+    KeysRecorded(KeyCode, Modifiers),
+    RecordKeysCancel
 }
 
 impl MacroMenu {
@@ -54,6 +115,10 @@ impl MacroMenu {
                 self.macro_data.macro_steps.splice(index..index+1, [val]);
                 self.is_modified = true
             },
+            MacroMenuMessage::NewVals(vals, index) => {
+                self.macro_data.macro_steps.splice(index..index+1, vals);
+                self.is_modified = true
+            },
             MacroMenuMessage::Removed(index) => {
                 self.macro_data.macro_steps.remove(index);
                 self.is_modified = true
@@ -64,6 +129,8 @@ impl MacroMenu {
             },
             MacroMenuMessage::EmitError(error) => {
                 *(self.macro_should_run.lock().unwrap()) = false;
+                self.capture = None;
+                self.debugger = None;
                 return Err(anyhow!(error))
             },
             MacroMenuMessage::PlayPressed => {
@@ -101,7 +168,10 @@ impl MacroMenu {
                 self.is_modified = false;
             },
             MacroMenuMessage::MacroDone => *(self.macro_should_run.lock().unwrap()) = false,
-            MacroMenuMessage::SettingsShow => self.show_settings = true,
+            MacroMenuMessage::SettingsShow => {
+                self.monitor_options = MonitorChoice::all_string_options();
+                self.show_settings = true;
+            },
             MacroMenuMessage::SettingsUpdateBreakWhileMacro(break_whole_macro) => {
                 self.macro_data.settings.break_whole_macro = break_whole_macro;
                 self.is_modified = true;
@@ -117,6 +187,44 @@ impl MacroMenu {
 
                 self.is_modified = true;
             },
+            MacroMenuMessage::SettingsUpdateSearchInterval(text) => {
+                if text == "" {
+                    self.macro_data.settings.search_interval_ms = 0;
+                }
+                
+                if let Ok(num_res) = text.parse() {
+                    self.macro_data.settings.search_interval_ms = num_res;
+                }
+
+                self.is_modified = true;
+            },
+            MacroMenuMessage::SettingsUpdateMonitor(text) => {
+                self.macro_data.settings.monitor = MonitorChoice::from_str(&text)?;
+                self.is_modified = true;
+            },
+            MacroMenuMessage::SettingsUpdateWindow(enabled) => {
+                self.macro_data.settings.window = match enabled {
+                    true => Some(WindowTarget::default()),
+                    false => None,
+                };
+                self.is_modified = true;
+            },
+            MacroMenuMessage::SettingsUpdateWindowTitle(title) => {
+                if let Some(window) = &mut self.macro_data.settings.window {
+                    window.title = title;
+                    self.is_modified = true;
+                }
+            },
+            MacroMenuMessage::SettingsUpdateWindowClass(class) => {
+                if let Some(window) = &mut self.macro_data.settings.window {
+                    window.class = class;
+                    self.is_modified = true;
+                }
+            },
+            MacroMenuMessage::SettingsUpdateOpener(opener) => {
+                self.macro_data.settings.opener = opener;
+                self.is_modified = true;
+            },
             MacroMenuMessage::SettingsDismiss => self.show_settings = false,
             MacroMenuMessage::SavePressed => {
                 let path = FileDialog::new()
@@ -134,12 +242,233 @@ impl MacroMenu {
 
                 self.is_modified = false;
             },
+            MacroMenuMessage::CaptureFor(index, slot) => {
+                self.capture = Some(ScreenCapture::new(index, slot));
+
+                return Ok(Command::perform(
+                    ScreenCapture::take_screenshot(self.macro_data.settings.clone()),
+                    |res| {
+                        match res {
+                            Ok(screenshot) => MacroMenuMessage::CaptureTaken(screenshot),
+                            Err(err) => MacroMenuMessage::EmitError("Failed to take a screenshot:\n".to_string() + &err.to_string()),
+                        }
+                    }
+                ))
+            },
+            MacroMenuMessage::CaptureTaken(screenshot) => {
+                if let Some(capture) = &mut self.capture {
+                    capture.set_screenshot(screenshot);
+                }
+            },
+            MacroMenuMessage::CaptureSelectionStart(x, y) => {
+                if let Some(capture) = &mut self.capture {
+                    capture.start_selection(x, y);
+                }
+            },
+            MacroMenuMessage::CaptureSelectionMove(x, y) => {
+                if let Some(capture) = &mut self.capture {
+                    capture.move_selection(x, y);
+                }
+            },
+            MacroMenuMessage::CaptureSelectionEnd(x, y) => {
+                if let Some(capture) = &mut self.capture {
+                    if let Some(image) = capture.finish_selection(x, y) {
+                        if let Some(step) = self.macro_data.macro_steps.get_mut(capture.step_index) {
+                            step.set_image(capture.image_slot, image);
+                            self.is_modified = true;
+                        }
+
+                        self.capture = None;
+                    }
+                }
+            },
+            MacroMenuMessage::CaptureCancel => self.capture = None,
+            MacroMenuMessage::EditImageFor(index, slot) => {
+                let image = self.macro_data.macro_steps.get(index)
+                    .and_then(|step| step.image(slot))
+                    .ok_or(anyhow!("The step has no image to edit"))?;
+
+                self.editor = Some(TemplateEditor::new(index, slot, image.clone()));
+            },
+            MacroMenuMessage::Editor(TemplateEditorMessage::Save) => {
+                if let Some(editor) = self.editor.take() {
+                    if let Some(step) = self.macro_data.macro_steps.get_mut(editor.step_index) {
+                        step.set_image(editor.image_slot, editor.image);
+                        self.is_modified = true;
+                    }
+                }
+            },
+            MacroMenuMessage::Editor(TemplateEditorMessage::Cancel) => self.editor = None,
+            MacroMenuMessage::Editor(msg) => {
+                if let Some(editor) = &mut self.editor {
+                    editor.update(msg);
+                }
+            },
+            MacroMenuMessage::TestMatchFor(index) => {
+                let target = self.macro_data.macro_steps.get(index)
+                    .and_then(|step| step.search_target())
+                    .ok_or(anyhow!("The step doesn't search for images"))?;
+
+                if target.images.is_empty() {
+                    return Err(anyhow!("The step has no image to test"));
+                }
+
+                self.debugger = Some(MatchDebugger::new(index, target.clone()));
+
+                return Ok(Command::perform(
+                    MatchDebugger::run(target, self.macro_data.settings.clone()),
+                    |res| {
+                        match res {
+                            Ok(report) => MacroMenuMessage::TestMatchDone(report),
+                            Err(err) => MacroMenuMessage::EmitError("Failed to test the step:\n".to_string() + &err.to_string()),
+                        }
+                    }
+                ))
+            },
+            MacroMenuMessage::TestMatchDone(report) => {
+                if let Some(debugger) = &mut self.debugger {
+                    debugger.set_result(report);
+                }
+            },
+            MacroMenuMessage::TestMatchClose => self.debugger = None,
+            MacroMenuMessage::CalibrateFor(index) => {
+                let target = self.macro_data.macro_steps.get(index)
+                    .and_then(|step| step.search_target())
+                    .ok_or(anyhow!("The step doesn't search for images"))?;
+
+                if target.images.is_empty() {
+                    return Err(anyhow!("The step has no image to calibrate"));
+                }
+
+                self.calibration = Some(CalibrationTool::new(index, target, self.macro_data.settings.clone()));
+            },
+            MacroMenuMessage::Calibration(CalibrationMessage::Apply) => {
+                if let Some(calibration) = self.calibration.take() {
+                    if let (Some(step), Some(allowed_diff)) = (self.macro_data.macro_steps.get_mut(calibration.step_index), calibration.suggestion()) {
+                        step.set_allowed_diff(allowed_diff);
+                        self.is_modified = true;
+                    }
+                }
+            },
+            MacroMenuMessage::Calibration(CalibrationMessage::Close) => self.calibration = None,
+            MacroMenuMessage::Calibration(msg) => {
+                if let Some(calibration) = &mut self.calibration {
+                    return Ok(calibration.update(msg)?.map(MacroMenuMessage::Calibration));
+                }
+            },
+            MacroMenuMessage::PickPositionFor(index) => {
+                self.recording = None;
+
+                // the waiting that is already going on keeps F8, another one couldn't get it
+                if let Some(picking) = &mut self.picking {
+                    picking.step_index = index;
+                    return Ok(Command::none());
+                }
+
+                let waiting = Arc::new(Mutex::new(true));
+                self.picking = Some(Picking { step_index: index, waiting: waiting.clone(), hotkey_error: None });
+
+                return Ok(Command::perform(
+                    spawn_blocking(move || wait_for_f8(waiting)),
+                    |res| MacroMenuMessage::PickPositionHotkey(res.map_err(|err| err.to_string()))
+                ))
+            },
+            MacroMenuMessage::PickPositionTaken | MacroMenuMessage::PickPositionHotkey(Ok(true)) => {
+                if let Some(picking) = self.picking.take() {
+                    let (x, y) = relative_cursor_position(&self.macro_data.settings)?;
+
+                    if let Some(step) = self.macro_data.macro_steps.get_mut(picking.step_index) {
+                        step.set_position(x.round() as i32, y.round() as i32);
+                        self.is_modified = true;
+                    }
+                }
+            },
+            MacroMenuMessage::PickPositionHotkey(Ok(false)) => (),
+            MacroMenuMessage::PickPositionHotkey(Err(error)) => {
+                if let Some(picking) = &mut self.picking {
+                    picking.hotkey_error = Some(error);
+                }
+            },
+            MacroMenuMessage::PickPositionCancel => self.picking = None,
+            MacroMenuMessage::RecordKeysFor(index) => {
+                self.picking = None;
+                self.recording = Some(index)
+            },
+            MacroMenuMessage::KeysRecorded(key_code, modifiers) => {
+                // modifiers on their own are only held for the key that comes after them
+                if let Some(key) = recorded_key(key_code) {
+                    if let Some(index) = self.recording.take() {
+                        if let Some(step) = self.macro_data.macro_steps.get_mut(index) {
+                            step.set_shortcut(key, recorded_flags(modifiers));
+                            self.is_modified = true;
+                        }
+                    }
+                }
+            },
+            MacroMenuMessage::RecordKeysCancel => self.recording = None,
         }
 
         Ok(Command::none())
     }
 
+    /// the window keeps the keyboard focus while the mouse is moved over other windows, so a key press
+    /// is enough to take the position without having to click anything
+    pub fn subscription(&self) -> Subscription<MacroMenuMessage> {
+        if self.recording.is_some() {
+            // every key is recorded, Esc included, so cancelling is left to the button in the side panel
+            return iced_native::subscription::events_with(|event, _status| {
+                match event {
+                    Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }) => Some(MacroMenuMessage::KeysRecorded(key_code, modifiers)),
+                    _ => None,
+                }
+            });
+        }
+
+        if self.picking.is_none() {
+            return Subscription::none();
+        }
+
+        iced_native::subscription::events_with(|event, _status| {
+            match event {
+                Event::Keyboard(keyboard::Event::KeyPressed { key_code: KeyCode::F8, .. }) => Some(MacroMenuMessage::PickPositionTaken),
+                Event::Keyboard(keyboard::Event::KeyPressed { key_code: KeyCode::Escape, .. }) => Some(MacroMenuMessage::PickPositionCancel),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn window_mode(&self) -> window::Mode {
+        if let Some(capture) = &self.capture {
+            return capture.window_mode();
+        }
+
+        if let Some(debugger) = &self.debugger {
+            return debugger.window_mode();
+        }
+
+        match &self.calibration {
+            Some(calibration) => calibration.window_mode(),
+            None => window::Mode::Windowed,
+        }
+    }
+
     pub fn view(&self) -> Element<MacroMenuMessage> {
+        if let Some(capture) = &self.capture {
+            return capture.view();
+        }
+
+        if let Some(editor) = &self.editor {
+            return editor.view().map(MacroMenuMessage::Editor);
+        }
+
+        if let Some(debugger) = &self.debugger {
+            return debugger.view();
+        }
+
+        if let Some(calibration) = &self.calibration {
+            return calibration.view().map(MacroMenuMessage::Calibration);
+        }
+
         let content = row()
         .push(
            self.macro_container() 
@@ -215,7 +544,7 @@ impl MacroMenu {
     }
 
     fn settings_card(&self) -> Element<MacroMenuMessage> {
-        let settings_body = column().push(
+        let mut settings_body = column().push(
             toggler(
                 Some("Stop whole macro on timeout".to_string()), 
                 self.macro_data.settings.break_whole_macro, 
@@ -227,9 +556,74 @@ impl MacroMenu {
             ).push(
                 self.my_numeric_input()
             )
+        ).push(
+            row().push(
+                text("Pause between searches in ms")
+            ).push(
+                text_input(
+                    "milliseconds",
+                    &format!("{}", self.macro_data.settings.search_interval_ms),
+                    MacroMenuMessage::SettingsUpdateSearchInterval
+                )
+            )
+        ).push(
+            row().push(
+                text("Search on")
+            ).push(
+                pick_list(
+                    self.monitor_options.clone(),
+                    Some(self.macro_data.settings.monitor.to_string()),
+                    MacroMenuMessage::SettingsUpdateMonitor
+                )
+            )
+            .spacing(5)
+        ).push(
+            row().push(
+                text("Open files with")
+            ).push(
+                text_input(
+                    "default application",
+                    &self.macro_data.settings.opener,
+                    MacroMenuMessage::SettingsUpdateOpener
+                )
+            )
+            .spacing(5)
+        ).push(
+            toggler(
+                Some("Only in a window".to_string()),
+                self.macro_data.settings.window.is_some(),
+                MacroMenuMessage::SettingsUpdateWindow
+            )
         )
         .spacing(7);
 
+        // regular expressions, an empty one matches any window
+        if let Some(window) = &self.macro_data.settings.window {
+            settings_body = settings_body.push(
+                row().push(
+                    text("Title")
+                ).push(
+                    text_input(
+                        "any title",
+                        &window.title,
+                        MacroMenuMessage::SettingsUpdateWindowTitle
+                    )
+                )
+                .spacing(5)
+            ).push(
+                row().push(
+                    text("Class")
+                ).push(
+                    text_input(
+                        "any class",
+                        &window.class,
+                        MacroMenuMessage::SettingsUpdateWindowClass
+                    )
+                )
+                .spacing(5)
+            );
+        }
+
         Card::new(
             text("Settings"),
             settings_body
@@ -299,8 +693,18 @@ impl MacroMenu {
                 macro_step_component(
                     i,
                     Some(macro_step.clone()),
+                    self.macro_data.settings.clone(),
                     MacroMenuMessage::NewVal,
                     MacroMenuMessage::Removed,
+                    |index, action| match action {
+                        StepAction::Capture(slot) => MacroMenuMessage::CaptureFor(index, slot),
+                        StepAction::Edit(slot) => MacroMenuMessage::EditImageFor(index, slot),
+                        StepAction::Test => MacroMenuMessage::TestMatchFor(index),
+                        StepAction::Calibrate => MacroMenuMessage::CalibrateFor(index),
+                        StepAction::Pick => MacroMenuMessage::PickPositionFor(index),
+                        StepAction::Record => MacroMenuMessage::RecordKeysFor(index),
+                        StepAction::Expand(steps) => MacroMenuMessage::NewVals(steps, index),
+                    },
                     MacroMenuMessage::EmitError
                 )
             )
@@ -331,23 +735,57 @@ impl MacroMenu {
             }
         }
 
+        let mut buttons = column().push(
+            self.run_stop_button()
+        ).push(
+            button(
+                text("Settings")
+            )
+            .on_press(MacroMenuMessage::SettingsShow)
+        ).push(
+            button(
+                text("Save as")
+            )
+            .on_press(MacroMenuMessage::SavePressed)
+        )
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        if let Some(picking) = &self.picking {
+            let hint = match &picking.hotkey_error {
+                None => "Move the mouse to the point and press F8, it works over other windows too".to_string(),
+                Some(error) => format!(
+                    "Move the mouse to the point and press F8 - only while this window has the focus, don't click other windows ({})",
+                    error
+                ),
+            };
+
+            buttons = buttons.push(
+                text(hint)
+                .horizontal_alignment(Horizontal::Center)
+            ).push(
+                button(
+                    text("Cancel")
+                )
+                .on_press(MacroMenuMessage::PickPositionCancel)
+            );
+        }
+
+        if self.recording.is_some() {
+            buttons = buttons.push(
+                text("Press the key with its modifiers held")
+                .horizontal_alignment(Horizontal::Center)
+            ).push(
+                button(
+                    text("Cancel")
+                )
+                .on_press(MacroMenuMessage::RecordKeysCancel)
+            );
+        }
+
         column().push(
             container(
-                column().push(
-                    self.run_stop_button()
-                ).push(
-                    button(
-                        text("Settings")
-                    )
-                    .on_press(MacroMenuMessage::SettingsShow)
-                ).push(
-                    button(
-                        text("Save as")
-                    )
-                    .on_press(MacroMenuMessage::SavePressed)
-                )
-                .spacing(10)
-                .align_items(Alignment::Center)
+                buttons
             )
             .height(Length::Shrink)
         ).push(
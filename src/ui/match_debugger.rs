@@ -0,0 +1,199 @@
+use anyhow::Result;
+use iced::{Color, Length, Rectangle, Alignment, window};
+use iced::image::Handle;
+use iced::pure::{Element, column, row, text, button, container, scrollable};
+use image::RgbaImage;
+
+use crate::macro_logic::{Candidate, ImageSlot, ImageTarget, Settings};
+
+use super::MacroMenuMessage;
+use super::screen_capture::ScreenCapture;
+use super::widgets::{pixel_handle, PixelView};
+
+const CANDIDATE_COUNT: usize = 5;
+
+/// the best places of the screen for every image of a step, so the accuracy can be set by looking at the scores
+/// instead of guessing
+pub struct MatchDebugger {
+    pub step_index: usize,
+    target: ImageTarget,
+    result: Option<MatchResult>      // None while the screenshot is taken and searched
+}
+
+/// what the step finds on a screenshot, searched for the same way the step does it
+#[derive(Debug, Clone)]
+pub struct MatchReport {
+    screenshot: RgbaImage,
+    region: Option<(u32, u32, u32, u32)>,   // where the images are searched, None when the anchor isn't on the screen
+    candidates: Vec<Vec<Candidate>>,        // for every image of the step, best first
+    used: Option<((u32, u32), ImageSlot)>   // what the step goes to
+}
+
+struct MatchResult {
+    screenshot: Handle,
+    width: u32,
+    height: u32,
+    region: Option<(u32, u32, u32, u32)>,
+    candidates: Vec<Vec<Candidate>>,
+    used: Option<((u32, u32), ImageSlot)>
+}
+
+impl MatchDebugger {
+    pub fn new(step_index: usize, target: ImageTarget) -> Self {
+        Self { step_index, target, result: None }
+    }
+
+    /// hides the window for the screenshot like capturing does, the searching is done here so the ui doesn't freeze
+    pub async fn run(target: ImageTarget, settings: Settings) -> Result<MatchReport> {
+        let screenshot = ScreenCapture::take_screenshot(settings).await?;
+        let templates = target.prepare()?;
+
+        // an anchor limits the search of the step, so the candidates only come from its region too
+        let region = target.search_region(&screenshot, &templates);
+
+        let candidates = match region {
+            Some(region) => target.best_candidates(&screenshot, &templates, region, CANDIDATE_COUNT),
+            None => Vec::new(),
+        };
+
+        let used = target.locate(&screenshot, &templates);
+
+        Ok(MatchReport { screenshot, region, candidates, used })
+    }
+
+    pub fn set_result(&mut self, report: MatchReport) {
+        self.result = Some(MatchResult {
+            screenshot: pixel_handle(&report.screenshot, 1),
+            width: report.screenshot.width(),
+            height: report.screenshot.height(),
+            region: report.region,
+            candidates: report.candidates,
+            used: report.used
+        });
+    }
+
+    pub fn window_mode(&self) -> window::Mode {
+        match self.result {
+            Some(_) => window::Mode::Windowed,
+            None => window::Mode::Hidden,
+        }
+    }
+
+    pub fn view(&self) -> Element<MacroMenuMessage> {
+        let result = match &self.result {
+            Some(result) => result,
+            None => return text("").into(),
+        };
+
+        let mut screen_view = PixelView::from_handle(result.screenshot.clone(), result.width, result.height, 1);
+        let mut list = column().spacing(5);
+
+        match (&self.target.anchor, result.region) {
+            (Some(_), Some((x, y, width, height))) => {
+                screen_view = screen_view.outline(
+                    Rectangle { x: x as f32, y: y as f32, width: width as f32, height: height as f32 },
+                    Color::from_rgb(0.0, 0.4, 1.0)
+                );
+                list = list.push(text("Searched only in the region around the anchor").color(Color::from_rgb(0.0, 0.4, 1.0)));
+            },
+            (Some(_), None) => {
+                list = list.push(text("The anchor isn't on the screen, so the step doesn't find anything").color(Color::from_rgb(1.0, 0.0, 0.0)));
+            },
+            _ => (),
+        }
+
+        for (image_index, (image, candidates)) in self.target.images.iter().zip(result.candidates.iter()).enumerate() {
+            if self.target.images.len() > 1 {
+                list = list.push(text(format!("Image {}", image_index + 1)));
+            }
+
+            if candidates.is_empty() {
+                list = list.push(text("Nothing to compare, the image is bigger than the searched area or fully transparent"));
+            }
+
+            // the search goes top to bottom, so the step uses the highest match and not necessarily the best one
+            let used = match result.used {
+                Some((position, ImageSlot::Template(index))) if index == image_index => Some(position),
+                _ => None,
+            };
+
+            let is_used = |candidate: &Candidate| used == Some((candidate.x, candidate.y));
+
+            let colors: Vec<Color> = candidates.iter().enumerate().map(|(rank, candidate)| {
+                match used {
+                    _ if is_used(candidate) => Color::from_rgb(0.0, 0.8, 0.0),
+                    None if rank == 0 => Color::from_rgb(1.0, 0.0, 0.0),
+                    _ => Color::from_rgb(1.0, 0.6, 0.0),
+                }
+            }).collect();
+
+            // later candidates are drawn first, so the best one stays on top where they touch
+            for (candidate, color) in candidates.iter().zip(colors.iter()).rev() {
+                screen_view = screen_view.outline(
+                    Rectangle { x: candidate.x as f32, y: candidate.y as f32, width: image.width() as f32, height: image.height() as f32 },
+                    *color
+                );
+            }
+
+            for (rank, (candidate, color)) in candidates.iter().zip(colors.iter()).enumerate() {
+                let verdict = match candidate.matches(self.target.allowed_diff) {
+                    _ if is_used(candidate) => "used by the step",
+                    true => "matches",
+                    false => "doesn't match",
+                };
+
+                list = list.push(
+                    text(format!(
+                        "{}. x {}, y {} - {:.1}% ({})",
+                        rank + 1, candidate.x, candidate.y, (1.0 - candidate.difference) * 100.0, verdict
+                    ))
+                    .color(*color)
+                );
+            }
+
+            // a match higher up than all of the best ones, it only just passes the accuracy
+            if let Some((x, y)) = used.filter(|_| !candidates.iter().any(is_used)) {
+                screen_view = screen_view.outline(
+                    Rectangle { x: x as f32, y: y as f32, width: image.width() as f32, height: image.height() as f32 },
+                    Color::from_rgb(0.0, 0.8, 0.0)
+                );
+                list = list.push(text(format!("x {}, y {} (used by the step)", x, y)).color(Color::from_rgb(0.0, 0.8, 0.0)));
+            }
+        }
+
+        column().push(
+            row().push(
+                text(format!("Best matches for step {}, it needs at least {:.0}%", self.step_index + 1, (1.0 - self.target.allowed_diff) * 100.0))
+            ).push(
+                button(
+                    text("Close")
+                )
+                .on_press(MacroMenuMessage::TestMatchClose)
+            )
+            .spacing(10)
+            .padding(5)
+            .align_items(Alignment::Center)
+        ).push(
+            row().push(
+                container(
+                    screen_view
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+            ).push(
+                container(
+                    scrollable(
+                        list
+                    )
+                )
+                .width(Length::Units(300))
+            )
+            .spacing(10)
+            .height(Length::Fill)
+        )
+        .padding(10)
+        .into()
+    }
+}
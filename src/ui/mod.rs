@@ -1,9 +1,15 @@
 mod base;
 mod main_menu;
 mod macro_menu;
+mod screen_capture;
+mod template_editor;
+mod match_debugger;
+mod calibration_tool;
+mod recorded_keys;
 
 pub mod style;
 pub mod components;
+pub mod widgets;
 
 pub use base::Base;
 pub use base::BaseMessage;
@@ -0,0 +1,150 @@
+use autopilot::key::Flag;
+use iced::keyboard::{KeyCode, Modifiers};
+
+use crate::macro_logic::Key;
+
+/// the key a step presses for a key pressed while recording, None for modifiers as they're recorded with
+/// the next key and for keys that steps can't press
+pub fn recorded_key(key_code: KeyCode) -> Option<Key> {
+    Some(match key_code {
+        KeyCode::Key1 => Key::Character('1'),
+        KeyCode::Key2 => Key::Character('2'),
+        KeyCode::Key3 => Key::Character('3'),
+        KeyCode::Key4 => Key::Character('4'),
+        KeyCode::Key5 => Key::Character('5'),
+        KeyCode::Key6 => Key::Character('6'),
+        KeyCode::Key7 => Key::Character('7'),
+        KeyCode::Key8 => Key::Character('8'),
+        KeyCode::Key9 => Key::Character('9'),
+        KeyCode::Key0 => Key::Character('0'),
+        KeyCode::A => Key::Character('a'),
+        KeyCode::B => Key::Character('b'),
+        KeyCode::C => Key::Character('c'),
+        KeyCode::D => Key::Character('d'),
+        KeyCode::E => Key::Character('e'),
+        KeyCode::F => Key::Character('f'),
+        KeyCode::G => Key::Character('g'),
+        KeyCode::H => Key::Character('h'),
+        KeyCode::I => Key::Character('i'),
+        KeyCode::J => Key::Character('j'),
+        KeyCode::K => Key::Character('k'),
+        KeyCode::L => Key::Character('l'),
+        KeyCode::M => Key::Character('m'),
+        KeyCode::N => Key::Character('n'),
+        KeyCode::O => Key::Character('o'),
+        KeyCode::P => Key::Character('p'),
+        KeyCode::Q => Key::Character('q'),
+        KeyCode::R => Key::Character('r'),
+        KeyCode::S => Key::Character('s'),
+        KeyCode::T => Key::Character('t'),
+        KeyCode::U => Key::Character('u'),
+        KeyCode::V => Key::Character('v'),
+        KeyCode::W => Key::Character('w'),
+        KeyCode::X => Key::Character('x'),
+        KeyCode::Y => Key::Character('y'),
+        KeyCode::Z => Key::Character('z'),
+        KeyCode::Grave => Key::Character('`'),
+        KeyCode::Minus => Key::Character('-'),
+        KeyCode::Equals => Key::Character('='),
+        KeyCode::LBracket => Key::Character('['),
+        KeyCode::RBracket => Key::Character(']'),
+        KeyCode::Backslash => Key::Character('\\'),
+        KeyCode::Semicolon => Key::Character(';'),
+        KeyCode::Apostrophe => Key::Character('\''),
+        KeyCode::Comma => Key::Character(','),
+        KeyCode::Period => Key::Character('.'),
+        KeyCode::Slash => Key::Character('/'),
+        KeyCode::Escape => Key::Escape,
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+        KeyCode::F13 => Key::F13,
+        KeyCode::F14 => Key::F14,
+        KeyCode::F15 => Key::F15,
+        KeyCode::F16 => Key::F16,
+        KeyCode::F17 => Key::F17,
+        KeyCode::F18 => Key::F18,
+        KeyCode::F19 => Key::F19,
+        KeyCode::F20 => Key::F20,
+        KeyCode::F21 => Key::F21,
+        KeyCode::F22 => Key::F22,
+        KeyCode::F23 => Key::F23,
+        KeyCode::F24 => Key::F24,
+        KeyCode::Snapshot => Key::PrintScreen,
+        KeyCode::Scroll => Key::ScrollLock,
+        KeyCode::Pause => Key::Pause,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Home => Key::Home,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::End => Key::End,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::Left => Key::LeftArrow,
+        KeyCode::Up => Key::UpArrow,
+        KeyCode::Right => Key::RightArrow,
+        KeyCode::Down => Key::DownArrow,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Enter => Key::Return,
+        KeyCode::Space => Key::Space,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Capital => Key::CapsLock,
+        KeyCode::Apps => Key::Menu,
+        KeyCode::Numlock => Key::NumLock,
+        KeyCode::Numpad0 => Key::Numpad0,
+        KeyCode::Numpad1 => Key::Numpad1,
+        KeyCode::Numpad2 => Key::Numpad2,
+        KeyCode::Numpad3 => Key::Numpad3,
+        KeyCode::Numpad4 => Key::Numpad4,
+        KeyCode::Numpad5 => Key::Numpad5,
+        KeyCode::Numpad6 => Key::Numpad6,
+        KeyCode::Numpad7 => Key::Numpad7,
+        KeyCode::Numpad8 => Key::Numpad8,
+        KeyCode::Numpad9 => Key::Numpad9,
+        KeyCode::NumpadAdd => Key::NumpadAdd,
+        KeyCode::NumpadSubtract => Key::NumpadSubtract,
+        KeyCode::NumpadMultiply => Key::NumpadMultiply,
+        KeyCode::NumpadDivide => Key::NumpadDivide,
+        KeyCode::NumpadDecimal => Key::NumpadDecimal,
+        KeyCode::NumpadEnter => Key::NumpadEnter,
+        KeyCode::VolumeUp => Key::VolumeUp,
+        KeyCode::VolumeDown => Key::VolumeDown,
+        KeyCode::Mute => Key::VolumeMute,
+        KeyCode::PlayPause => Key::MediaPlayPause,
+        KeyCode::NextTrack => Key::MediaNext,
+        KeyCode::PrevTrack => Key::MediaPrevious,
+        KeyCode::MediaStop => Key::MediaStop,
+        _ => return None,
+    })
+}
+
+/// the modifiers held with a recorded key as the flags of a step
+pub fn recorded_flags(modifiers: Modifiers) -> Vec<Flag> {
+    let mut flags = Vec::new();
+
+    if modifiers.control() {
+        flags.push(Flag::Control);
+    }
+
+    if modifiers.alt() {
+        flags.push(Flag::Alt);
+    }
+
+    if modifiers.shift() {
+        flags.push(Flag::Shift);
+    }
+
+    if modifiers.logo() {
+        flags.push(Flag::Meta);
+    }
+
+    flags
+}
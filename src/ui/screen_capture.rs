@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use async_std::task::sleep as async_sleep;
+use iced::{Color, Length, Rectangle, Alignment, window};
+use iced::image::Handle;
+use iced::pure::{Element, column, row, text, button, container};
+use image::{RgbaImage, GenericImageView};
+
+use crate::macro_logic::{self, ImageSlot, Settings};
+
+use super::MacroMenuMessage;
+use super::widgets::{pixel_handle, PixelView};
+
+const HIDE_DELAY: Duration = Duration::from_millis(300);       // long enough for the window to be gone from the screenshot
+
+/// cutting an image for a step out of a screenshot, the window is hidden while the screenshot is taken
+/// and then shows it fullscreen so the selection can be done on the real size of things
+pub struct ScreenCapture {
+    pub step_index: usize,
+    pub image_slot: ImageSlot,
+    screenshot: Option<(RgbaImage, Handle)>,     // None while waiting for the screenshot
+    selection_start: Option<(f64, f64)>,
+    selection_end: (f64, f64)
+}
+
+impl ScreenCapture {
+    pub fn new(step_index: usize, image_slot: ImageSlot) -> Self {
+        Self { step_index, image_slot, screenshot: None, selection_start: None, selection_end: (0.0, 0.0) }
+    }
+
+    /// the target window or the chosen monitors of the macro, the same picture its steps search in
+    pub async fn take_screenshot(settings: Settings) -> Result<RgbaImage> {
+        async_sleep(HIDE_DELAY).await;
+
+        Ok(macro_logic::capture_for(&settings)?.image)
+    }
+
+    pub fn set_screenshot(&mut self, screenshot: RgbaImage) {
+        let handle = pixel_handle(&screenshot, 1);
+
+        self.screenshot = Some((screenshot, handle));
+    }
+
+    pub fn window_mode(&self) -> window::Mode {
+        match self.screenshot {
+            Some(_) => window::Mode::Fullscreen,
+            None => window::Mode::Hidden,
+        }
+    }
+
+    pub fn start_selection(&mut self, x: f64, y: f64) {
+        self.selection_start = Some((x, y));
+        self.selection_end = (x, y);
+    }
+
+    pub fn move_selection(&mut self, x: f64, y: f64) {
+        self.selection_end = (x, y);
+    }
+
+    /// the selected part of the screenshot, None if the selection has no area so the user can try again
+    pub fn finish_selection(&mut self, x: f64, y: f64) -> Option<RgbaImage> {
+        self.selection_end = (x, y);
+
+        let selection = self.selection();
+        self.selection_start = None;
+
+        let (screenshot, _) = self.screenshot.as_ref()?;
+        let (left, top, width, height) = selection?;
+
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        Some(screenshot.view(left, top, width, height).to_image())
+    }
+
+    /// whole pixels covered by the selection as x, y, width, height
+    fn selection(&self) -> Option<(u32, u32, u32, u32)> {
+        let (start_x, start_y) = self.selection_start?;
+        let (end_x, end_y) = self.selection_end;
+
+        let (left, top) = (start_x.min(end_x).floor(), start_y.min(end_y).floor());
+        let (right, bottom) = (start_x.max(end_x).ceil(), start_y.max(end_y).ceil());
+
+        Some((left as u32, top as u32, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    pub fn view(&self) -> Element<MacroMenuMessage> {
+        let (screenshot, handle) = match &self.screenshot {
+            Some(screenshot) => screenshot,
+            None => return text("").into(),
+        };
+
+        let mut screen_view = PixelView::from_handle(handle.clone(), screenshot.width(), screenshot.height(), 1)
+            .on_press(MacroMenuMessage::CaptureSelectionStart)
+            .on_drag(MacroMenuMessage::CaptureSelectionMove)
+            .on_release(MacroMenuMessage::CaptureSelectionEnd);
+
+        if let Some((x, y, width, height)) = self.selection() {
+            screen_view = screen_view.outline(
+                Rectangle { x: x as f32, y: y as f32, width: width as f32, height: height as f32 },
+                Color::from_rgb(1.0, 0.0, 0.0)
+            );
+        }
+
+        column().push(
+            row().push(
+                text("Drag a rectangle around the part of the screen to use as the image")
+            ).push(
+                button(
+                    text("Cancel")
+                )
+                .on_press(MacroMenuMessage::CaptureCancel)
+            )
+            .spacing(10)
+            .padding(5)
+            .align_items(Alignment::Center)
+        ).push(
+            container(
+                screen_view
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+        )
+        .into()
+    }
+}
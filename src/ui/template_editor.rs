@@ -0,0 +1,225 @@
+use iced::{Color, Length, Rectangle, Alignment};
+use iced::pure::{Element, column, row, text, button, container, pick_list};
+use image::{RgbaImage, Rgba};
+
+use crate::macro_logic::{self, ImageSlot};
+
+use super::style::TextButton;
+use super::widgets::pixel_view;
+
+const EDITOR_WIDTH: u32 = 800;
+const EDITOR_HEIGHT: u32 = 500;
+const MAX_EDITOR_ZOOM: u32 = 16;
+const BRUSH_SIZES: [u32; 5] = [1, 2, 4, 8, 16];
+const MASK_COLOR: [u8; 3] = [255, 0, 255];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTool {
+    Crop,
+    Mask,
+    Unmask
+}
+
+#[derive(Debug, Clone)]
+pub enum TemplateEditorMessage {
+    SelectTool(EditorTool),
+    BrushSize(u32),
+    Press(f64, f64),
+    Drag(f64, f64),
+    Release(f64, f64),
+    AutoTrim,
+    Reset,
+    Save,
+    Cancel
+}
+
+/// crop, trim and mask the image of a step, the result replaces the image only when saved
+pub struct TemplateEditor {
+    pub step_index: usize,
+    pub image_slot: ImageSlot,
+    original: RgbaImage,
+    pub image: RgbaImage,
+    tool: EditorTool,
+    brush_size: u32,
+    selection_start: Option<(f64, f64)>,
+    selection_end: (f64, f64)
+}
+
+impl TemplateEditor {
+    pub fn new(step_index: usize, image_slot: ImageSlot, image: RgbaImage) -> Self {
+        Self {
+            step_index,
+            image_slot,
+            original: image.clone(),
+            image,
+            tool: EditorTool::Crop,
+            brush_size: 4,
+            selection_start: None,
+            selection_end: (0.0, 0.0)
+        }
+    }
+
+    /// everything except saving and canceling, those are handled by the owner
+    pub fn update(&mut self, message: TemplateEditorMessage) {
+        match message {
+            TemplateEditorMessage::SelectTool(tool) => self.tool = tool,
+            TemplateEditorMessage::BrushSize(size) => self.brush_size = size,
+            TemplateEditorMessage::Press(x, y) => {
+                self.selection_start = Some((x, y));
+                self.selection_end = (x, y);
+                self.paint(x, y);
+            },
+            TemplateEditorMessage::Drag(x, y) => {
+                self.selection_end = (x, y);
+                self.paint(x, y);
+            },
+            TemplateEditorMessage::Release(x, y) => {
+                self.selection_end = (x, y);
+
+                if self.tool == EditorTool::Crop {
+                    if let Some((left, top, width, height)) = self.selection() {
+                        if let Some(cropped) = macro_logic::crop(&self.image, left, top, width, height) {
+                            self.image = cropped;
+                        }
+                    }
+                }
+
+                self.selection_start = None;
+            },
+            TemplateEditorMessage::AutoTrim => self.image = macro_logic::auto_trim(&self.image),
+            TemplateEditorMessage::Reset => self.image = self.original.clone(),
+            TemplateEditorMessage::Save | TemplateEditorMessage::Cancel => (),
+        }
+    }
+
+    fn paint(&mut self, x: f64, y: f64) {
+        let masked = match self.tool {
+            EditorTool::Crop => return,
+            EditorTool::Mask => true,
+            EditorTool::Unmask => false,
+        };
+
+        // the brush is centered on the pixel under the cursor
+        let offset = (self.brush_size as i64 - 1) / 2;
+        macro_logic::set_mask(&mut self.image, x.floor() as i64 - offset, y.floor() as i64 - offset, self.brush_size, self.brush_size, masked);
+    }
+
+    /// whole pixels covered by the crop selection as x, y, width, height
+    fn selection(&self) -> Option<(u32, u32, u32, u32)> {
+        let (start_x, start_y) = self.selection_start?;
+        let (end_x, end_y) = self.selection_end;
+
+        let (left, top) = (start_x.min(end_x).floor(), start_y.min(end_y).floor());
+        let (right, bottom) = (start_x.max(end_x).ceil(), start_y.max(end_y).ceil());
+
+        Some((left as u32, top as u32, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    pub fn view(&self) -> Element<TemplateEditorMessage> {
+        let zoom = (EDITOR_WIDTH / self.image.width().max(1))
+            .min(EDITOR_HEIGHT / self.image.height().max(1))
+            .clamp(1, MAX_EDITOR_ZOOM);
+
+        let mut editor_view = pixel_view(&mask_preview(&self.image), zoom)
+            .on_press(TemplateEditorMessage::Press)
+            .on_drag(TemplateEditorMessage::Drag)
+            .on_release(TemplateEditorMessage::Release);
+
+        if self.tool == EditorTool::Crop {
+            if let Some((x, y, width, height)) = self.selection() {
+                editor_view = editor_view.outline(
+                    Rectangle { x: x as f32, y: y as f32, width: width as f32, height: height as f32 },
+                    Color::from_rgb(1.0, 0.0, 0.0)
+                );
+            }
+        }
+
+        let mut status = format!("{} x {} pixels", self.image.width(), self.image.height());
+
+        if !macro_logic::has_opaque_pixels(&self.image) {
+            status += " - everything is masked, the image can't be searched for";
+        }
+
+        column().push(
+            row()
+                .push(self.tool_button("Crop", EditorTool::Crop))
+                .push(self.tool_button("Mask", EditorTool::Mask))
+                .push(self.tool_button("Unmask", EditorTool::Unmask))
+                .push(text("Brush"))
+                .push(
+                    pick_list(
+                        BRUSH_SIZES.to_vec(),
+                        Some(self.brush_size),
+                        TemplateEditorMessage::BrushSize
+                    )
+                )
+                .push(
+                    button(text("Auto-trim"))
+                    .on_press(TemplateEditorMessage::AutoTrim)
+                )
+                .push(
+                    button(text("Reset"))
+                    .on_press(TemplateEditorMessage::Reset)
+                )
+                .spacing(10)
+                .align_items(Alignment::Center)
+        ).push(
+            row().push(
+                container(
+                    editor_view
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+            ).push(
+                // the result at its real size, the way it will be searched for
+                column()
+                    .push(text("Result"))
+                    .push(pixel_view(&self.image, 1))
+                    .push(text(status))
+                    .spacing(5)
+                    .width(Length::Units(200))
+            )
+            .spacing(10)
+            .height(Length::Fill)
+        ).push(
+            row().push(
+                button(text("Save"))
+                .on_press(TemplateEditorMessage::Save)
+            ).push(
+                button(text("Cancel"))
+                .on_press(TemplateEditorMessage::Cancel)
+            )
+            .spacing(10)
+        )
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+
+    fn tool_button(&self, label: &str, tool: EditorTool) -> Element<TemplateEditorMessage> {
+        let mut tool_button = button(text(label)).on_press(TemplateEditorMessage::SelectTool(tool));
+
+        if self.tool != tool {
+            tool_button = tool_button.style(TextButton::Normal);
+        }
+
+        tool_button.into()
+    }
+}
+
+/// masked pixels are shown as a checkerboard of the mask color, so they can't be confused with the background
+fn mask_preview(image: &RgbaImage) -> RgbaImage {
+    RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = *image.get_pixel(x, y);
+
+        if macro_logic::is_opaque(pixel.0[3]) {
+            Rgba([pixel.0[0], pixel.0[1], pixel.0[2], 255])
+        } else if (x + y) % 2 == 0 {
+            Rgba([MASK_COLOR[0], MASK_COLOR[1], MASK_COLOR[2], 255])
+        } else {
+            Rgba([pixel.0[0] / 2, pixel.0[1] / 2, pixel.0[2] / 2, 255])
+        }
+    })
+}
@@ -0,0 +1,5 @@
+mod pixel_view;
+
+pub use pixel_view::pixel_view;
+pub use pixel_view::pixel_handle;
+pub use pixel_view::PixelView;
@@ -0,0 +1,314 @@
+// shows an image scaled up without smoothing, so single pixels of a template can be seen and clicked on
+// iced 0.4 only has linear filtering for images, so the image is scaled up on the cpu by a whole number
+// and drawn at exactly that size, which keeps the edges of the pixels sharp
+
+use iced::image::Handle;
+use iced_native::{layout, mouse, renderer, event, Clipboard, Color, Event, Layout, Length, Point, Rectangle, Shell, Size};
+use iced_pure::widget::Widget;
+use iced_pure::widget::tree::{self, Tree};
+use iced_pure::Element;
+use image::RgbaImage;
+
+const CROSSHAIR_SIZE: f32 = 7.0;
+const CROSSHAIR_THICKNESS: f32 = 1.0;
+const OUTLINE_WIDTH: f32 = 2.0;
+
+pub struct PixelView<Message> {
+    handle: Handle,
+    image_width: u32,
+    image_height: u32,
+    zoom: u32,
+    crosshair: Option<(f64, f64)>,      // in pixels of the image
+    outlines: Vec<(Rectangle, Color)>,  // same
+    on_press: Option<Box<dyn Fn(f64, f64) -> Message>>,
+    on_drag: Option<Box<dyn Fn(f64, f64) -> Message>>,
+    on_release: Option<Box<dyn Fn(f64, f64) -> Message>>
+}
+
+#[derive(Default)]
+struct State {
+    is_pressed: bool
+}
+
+impl<Message> PixelView<Message> {
+    pub fn new(image: &RgbaImage, zoom: u32) -> Self {
+        let zoom = zoom.max(1);
+
+        Self::from_handle(pixel_handle(image, zoom), image.width(), image.height(), zoom)
+    }
+
+    /// for big images that are shown many times, so they're converted once, the handle has to come from `pixel_handle`
+    pub fn from_handle(handle: Handle, image_width: u32, image_height: u32, zoom: u32) -> Self {
+        Self {
+            handle,
+            image_width,
+            image_height,
+            zoom,
+            crosshair: None,
+            outlines: Vec::new(),
+            on_press: None,
+            on_drag: None,
+            on_release: None
+        }
+    }
+
+    pub fn crosshair(self, crosshair: Option<(f64, f64)>) -> Self {
+        Self {
+            crosshair,
+            ..self
+        }
+    }
+
+    /// rectangles drawn around parts of the image, e.g. a selection or where a template was found
+    pub fn outline(mut self, rectangle: Rectangle, color: Color) -> Self {
+        self.outlines.push((rectangle, color));
+        self
+    }
+
+    /// gets the pixel of the image that was clicked, with the fraction of where in the pixel it was
+    pub fn on_press(self, on_press: impl Fn(f64, f64) -> Message + 'static) -> Self {
+        Self {
+            on_press: Some(Box::new(on_press)),
+            ..self
+        }
+    }
+
+    /// the mouse moved while the button is held after pressing on the image, clamped to the image
+    pub fn on_drag(self, on_drag: impl Fn(f64, f64) -> Message + 'static) -> Self {
+        Self {
+            on_drag: Some(Box::new(on_drag)),
+            ..self
+        }
+    }
+
+    /// the mouse button was let go after pressing on the image, clamped to the image
+    pub fn on_release(self, on_release: impl Fn(f64, f64) -> Message + 'static) -> Self {
+        Self {
+            on_release: Some(Box::new(on_release)),
+            ..self
+        }
+    }
+
+    /// where the image is drawn inside of the widget and how many screen pixels one image pixel takes
+    fn image_bounds(&self, bounds: Rectangle) -> (Rectangle, f32) {
+        let scale = (bounds.width / self.image_width as f32).min(bounds.height / self.image_height as f32);
+        let (width, height) = (self.image_width as f32 * scale, self.image_height as f32 * scale);
+
+        (
+            Rectangle {
+                x: bounds.x + (bounds.width - width) / 2.0,
+                y: bounds.y + (bounds.height - height) / 2.0,
+                width,
+                height
+            },
+            scale
+        )
+    }
+
+    fn image_position(&self, bounds: Rectangle, cursor_position: Point) -> (f64, f64) {
+        let (image_bounds, scale) = self.image_bounds(bounds);
+
+        (
+            ((cursor_position.x - image_bounds.x) / scale).clamp(0.0, self.image_width as f32) as f64,
+            ((cursor_position.y - image_bounds.y) / scale).clamp(0.0, self.image_height as f32) as f64
+        )
+    }
+}
+
+impl<Message, Renderer> Widget<Message, Renderer> for PixelView<Message>
+where
+    Renderer: iced_native::image::Renderer,
+    <Renderer as iced_native::image::Renderer>::Handle: From<Handle>
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn width(&self) -> Length {
+        Length::Units((self.image_width * self.zoom) as u16)
+    }
+
+    fn height(&self) -> Length {
+        Length::Units((self.image_height * self.zoom) as u16)
+    }
+
+    fn layout(&self, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits
+            .width(<Self as Widget<Message, Renderer>>::width(self))
+            .height(<Self as Widget<Message, Renderer>>::height(self))
+            .resolve(Size::new((self.image_width * self.zoom) as f32, (self.image_height * self.zoom) as f32));
+
+        layout::Node::new(size)
+    }
+
+    fn draw(
+        &self,
+        _state: &Tree,
+        renderer: &mut Renderer,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle
+    ) {
+        let (image_bounds, scale) = self.image_bounds(layout.bounds());
+
+        iced_native::image::Renderer::draw(renderer, self.handle.clone().into(), image_bounds);
+
+        for (rectangle, color) in &self.outlines {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x: image_bounds.x + rectangle.x * scale - OUTLINE_WIDTH,
+                        y: image_bounds.y + rectangle.y * scale - OUTLINE_WIDTH,
+                        width: rectangle.width * scale + OUTLINE_WIDTH * 2.0,
+                        height: rectangle.height * scale + OUTLINE_WIDTH * 2.0
+                    },
+                    border_radius: 0.0,
+                    border_width: OUTLINE_WIDTH,
+                    border_color: *color
+                },
+                Color::TRANSPARENT
+            );
+        }
+
+        if let Some((x, y)) = self.crosshair {
+            // points outside of the image are drawn on its edge
+            let center_x = image_bounds.x + (x as f32 * scale).clamp(0.0, image_bounds.width);
+            let center_y = image_bounds.y + (y as f32 * scale).clamp(0.0, image_bounds.height);
+
+            // dark outline under the red lines so the crosshair is visible on any background
+            for (thickness, color) in [(CROSSHAIR_THICKNESS + 2.0, Color::BLACK), (CROSSHAIR_THICKNESS, Color::from_rgb(1.0, 0.0, 0.0))] {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: center_x - CROSSHAIR_SIZE,
+                            y: center_y - thickness / 2.0,
+                            width: CROSSHAIR_SIZE * 2.0,
+                            height: thickness
+                        },
+                        border_radius: 0.0,
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT
+                    },
+                    color
+                );
+
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: center_x - thickness / 2.0,
+                            y: center_y - CROSSHAIR_SIZE,
+                            width: thickness,
+                            height: CROSSHAIR_SIZE * 2.0
+                        },
+                        border_radius: 0.0,
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT
+                    },
+                    color
+                );
+            }
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if !self.image_bounds(bounds).0.contains(cursor_position) {
+                    return event::Status::Ignored;
+                }
+
+                state.is_pressed = true;
+
+                if let Some(on_press) = &self.on_press {
+                    let (x, y) = self.image_position(bounds, cursor_position);
+                    shell.publish(on_press(x, y));
+
+                    return event::Status::Captured;
+                }
+            },
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if state.is_pressed => {
+                if let Some(on_drag) = &self.on_drag {
+                    let (x, y) = self.image_position(bounds, cursor_position);
+                    shell.publish(on_drag(x, y));
+
+                    return event::Status::Captured;
+                }
+            },
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if state.is_pressed => {
+                state.is_pressed = false;
+
+                if let Some(on_release) = &self.on_release {
+                    let (x, y) = self.image_position(bounds, cursor_position);
+                    shell.publish(on_release(x, y));
+
+                    return event::Status::Captured;
+                }
+            },
+            _ => ()
+        }
+
+        event::Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        _renderer: &Renderer
+    ) -> mouse::Interaction {
+        if self.on_press.is_some() && self.image_bounds(layout.bounds()).0.contains(cursor_position) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::Idle
+        }
+    }
+}
+
+impl<'a, Message, Renderer> From<PixelView<Message>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::image::Renderer + 'a,
+    <Renderer as iced_native::image::Renderer>::Handle: From<Handle>
+{
+    fn from(pixel_view: PixelView<Message>) -> Self {
+        Element::new(pixel_view)
+    }
+}
+
+pub fn pixel_view<Message>(image: &RgbaImage, zoom: u32) -> PixelView<Message> {
+    PixelView::new(image, zoom)
+}
+
+/// nearest neighbour scaling, converted to the BGRA that iced expects
+pub fn pixel_handle(image: &RgbaImage, zoom: u32) -> Handle {
+    let (width, height) = (image.width() * zoom, image.height() * zoom);
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = image.get_pixel(x / zoom, y / zoom).0;
+            pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+        }
+    }
+
+    Handle::from_pixels(width, height, pixels)
+}